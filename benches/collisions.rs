@@ -0,0 +1,135 @@
+//! Mesure la détection large des collisions quand le nombre d'objets grandit.
+//!
+//! `pairs` compare la recherche des paires proches par la grille à la double boucle
+//! sur tous les objets, pour de simples cercles ; `bounces` fait de même pour les rebonds
+//! entre astéroïdes, qui comparent ensuite leurs contours, dans le monde de la partie.
+//!
+//! ```sh
+//! cargo bench --bench collisions
+//! ```
+use asteroid::config::AsteroidConfig;
+use asteroid::{Asteroid, GameWorld, SpatialHash, StellarObject};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use macroquad::prelude::*;
+use ::rand::{rngs::SmallRng, Rng, SeedableRng};
+
+const SCREEN: (f32, f32) = (4000.0, 3000.0);
+const RADIUS: f32 = 10.0;
+const COUNTS: [usize; 3] = [100, 1000, 5000];
+/// Nombres d'astéroïdes dans `bounces` : les astéroïdes bouclent sur le monde de la partie,
+/// qui ne grandit pas avec eux.
+const BOUNCE_COUNTS: [usize; 3] = [25, 100, 400];
+
+/// Des objets répartis au hasard sur l'écran.
+fn positions(count: usize) -> Vec<Vec2> {
+    let mut rng = SmallRng::seed_from_u64(0);
+    (0..count).map(|_| vec2(rng.gen_range(0.0..SCREEN.0), rng.gen_range(0.0..SCREEN.1))).collect()
+}
+
+/// Compte les paires d'objets dont les cercles se touchent, en les comparant tous deux à deux.
+fn brute_force(positions: &[Vec2]) -> usize {
+    let mut pairs = 0;
+    for (j, b) in positions.iter().enumerate() {
+        pairs += positions[..j].iter().filter(|a| a.distance(*b) < 2.0 * RADIUS).count();
+    }
+    pairs
+}
+
+/// Compte les mêmes paires en ne comparant que les voisins trouvés par la grille.
+fn spatial_hash(grid: &mut SpatialHash, positions: &[Vec2]) -> usize {
+    grid.clear();
+    let mut nearby = Vec::new();
+    let mut pairs = 0;
+    for (j, b) in positions.iter().enumerate() {
+        grid.query(*b, RADIUS, &mut nearby);
+        grid.insert(j, *b, RADIUS);
+        pairs += nearby.iter().filter(|&&i| positions[i].distance(*b) < 2.0 * RADIUS).count();
+    }
+    pairs
+}
+
+fn pairs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pairs");
+    for count in COUNTS {
+        let positions = positions(count);
+        let mut grid = SpatialHash::new(SCREEN, 4.0 * RADIUS);
+        assert_eq!(brute_force(&positions), spatial_hash(&mut grid, &positions));
+
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::new("brute_force", count), &positions, |b, positions| {
+            b.iter(|| brute_force(positions))
+        });
+        group.bench_with_input(BenchmarkId::new("spatial_hash", count), &positions, |b, positions| {
+            b.iter(|| spatial_hash(&mut grid, positions))
+        });
+    }
+    group.finish();
+}
+
+/// Des astéroïdes de toutes tailles répartis au hasard dans le monde de la partie.
+fn asteroids(count: usize) -> Vec<Asteroid> {
+    let mut rng = SmallRng::seed_from_u64(0);
+    let config = AsteroidConfig::default();
+    let (width, height) = GameWorld::SIZE;
+    (0..count)
+        .map(|_| {
+            let size = [config.init_size, config.medium_size, config.small_size][rng.gen_range(0..3)];
+            let mut asteroid = Asteroid::with_config(size, config, &mut rng);
+            asteroid.set_position(vec2(rng.gen_range(0.0..width), rng.gen_range(0.0..height)));
+            asteroid
+        })
+        .collect()
+}
+
+/// Fait rebondir les astéroïdes en comparant tous les contours deux à deux.
+fn bounce_brute_force(asteroids: &mut [Asteroid]) -> usize {
+    let mut bounces = 0;
+    for j in 0..asteroids.len() {
+        let (left, right) = asteroids.split_at_mut(j);
+        bounces += left.iter_mut().filter_map(|a| a.bounce(&mut right[0])).count();
+    }
+    bounces
+}
+
+/// Fait rebondir les astéroïdes en ne comparant que les voisins trouvés par la grille,
+/// comme le fait la partie.
+fn bounce_spatial_hash(grid: &mut SpatialHash, asteroids: &mut [Asteroid]) -> usize {
+    grid.clear();
+    let mut nearby = Vec::new();
+    let mut bounces = 0;
+    for j in 0..asteroids.len() {
+        let (left, right) = asteroids.split_at_mut(j);
+        let reach = right[0].get_size() * Asteroid::OUTLINE_MAX_RADIUS;
+        grid.query(right[0].get_position(), reach, &mut nearby);
+        grid.insert(j, right[0].get_position(), reach);
+        bounces += nearby.iter().filter_map(|&i| left[i].bounce(&mut right[0])).count();
+    }
+    bounces
+}
+
+fn bounces(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bounces");
+    group.sample_size(20);
+    for count in BOUNCE_COUNTS {
+        let asteroids = asteroids(count);
+        let cell_size = 2.0 * AsteroidConfig::default().init_size * Asteroid::OUTLINE_MAX_RADIUS;
+        let mut grid = SpatialHash::new(GameWorld::SIZE, cell_size);
+        assert_eq!(bounce_brute_force(&mut asteroids.clone()), bounce_spatial_hash(&mut grid, &mut asteroids.clone()));
+
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_function(BenchmarkId::new("brute_force", count), |b| {
+            b.iter_batched_ref(|| asteroids.clone(), |asteroids| bounce_brute_force(asteroids), BatchSize::LargeInput)
+        });
+        group.bench_function(BenchmarkId::new("spatial_hash", count), |b| {
+            b.iter_batched_ref(
+                || asteroids.clone(),
+                |asteroids| bounce_spatial_hash(&mut grid, asteroids),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, pairs, bounces);
+criterion_main!(benches);
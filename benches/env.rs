@@ -0,0 +1,37 @@
+//! Mesure le débit de l'environnement d'apprentissage, sans fenêtre : des épisodes
+//! joués avec des commandes au hasard, observation comprise à chaque pas.
+//!
+//! ```sh
+//! cargo bench --bench env
+//! ```
+use asteroid::env::{EnvConfig, Environment};
+use asteroid::PlayerInput;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use ::rand::{rngs::SmallRng, Rng, SeedableRng};
+
+/// Nombre de pas par itération mesurée.
+const STEPS: u64 = 1000;
+
+fn steps(c: &mut Criterion) {
+    let mut env = Environment::new(EnvConfig::default()).unwrap();
+    let mut rng = SmallRng::seed_from_u64(0);
+    let mut seed = 0;
+    env.reset(seed);
+
+    let mut group = c.benchmark_group("env");
+    group.throughput(Throughput::Elements(STEPS));
+    group.bench_function("random_agent", |b| b.iter(|| {
+        for _ in 0..STEPS {
+            let (observation, _, done) = env.step(&PlayerInput::from_bits(rng.gen_range(0..64)).unwrap());
+            criterion::black_box(observation.to_vec(env.config()));
+            if done {
+                seed += 1;
+                env.reset(seed);
+            }
+        }
+    }));
+    group.finish();
+}
+
+criterion_group!(benches, steps);
+criterion_main!(benches);
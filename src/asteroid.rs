@@ -1,25 +1,46 @@
 use std::f32::consts::PI;
 
 use macroquad::prelude::*;
-use ::rand::{thread_rng, Rng};
+use ::rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::config::AsteroidConfig;
+use crate::polygon;
+use crate::torus;
+use crate::world::GameWorld;
 use crate::stellarobject::StellarObject;
 
-/// Représente un astéroïde dans le jeu.
-#[derive(Clone)]
+/// Représente un astéroïde dans le jeu : un polygone irrégulier qui tourne sur lui-même.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Asteroid {
     position: Vec2,  // Position actuelle de l'astéroïde
-    speed: Vec2,     // Vitesse de déplacement de l'astéroïde
+    previous_position: Vec2, // Position au pas de simulation précédent
+    speed: Vec2,     // Vitesse de déplacement de l'astéroïde (unités par seconde)
     size: f32,       // Taille de l'astéroïde
-    screen_size : (f32,f32), // Taille de la fenetre de jeu 
+    shape: Vec<Vec2>, // Contour irrégulier autour de l'origine, sans rotation
+    rotation: f32,   // Rotation actuelle du contour en radians
+    spin: f32,       // Vitesse de rotation en radians par seconde
+    config: AsteroidConfig, // Réglages des astéroïdes (tailles, vitesse)
 }
 
 impl Asteroid {
+    // Valeurs par défaut, remplaçables par un fichier de réglages (voir `AsteroidConfig`).
+
     /// La taille initiale d'un astéroïde.
     pub const ASTEROID_INIT_SIZE: f32 = 50.0;
     /// La taille d'un astéroïde moyen.
     pub const ASTEROID_MEDIUM_SIZE: f32 = 25.0;
     /// La taille d'un astéroïde petit.
     pub const ASTEROID_SMALL_SIZE: f32 = 12.5;
+    /// La vitesse d'un astéroïde en unités par seconde.
+    pub const ASTEROID_SPEED: f32 = 60.0;
+    /// Demi-angle de l'éventail des fragments autour de la direction de l'impact, en radians.
+    pub const SPLIT_SPREAD: f32 = PI / 4.0;
+    /// Ecart aléatoire maximal appliqué à l'éventail des fragments, en radians.
+    pub const SPLIT_JITTER: f32 = 0.2;
+    /// Distance maximale des sommets du contour au centre, en proportion de la taille.
+    pub const OUTLINE_MAX_RADIUS: f32 = 1.1;
+    /// Vitesse de rotation maximale d'un astéroïde, en radians par seconde.
+    pub const MAX_SPIN: f32 = 1.0;
 
 
     /// Crée une nouvelle instance d'Asteroid avec une taille spécifiée.
@@ -27,43 +48,108 @@ impl Asteroid {
     /// # Arguments
     /// 
     /// * `size` - La taille de l'astéroïde à créer.
-    /// * `screen_size` - La taille de la fenetre de jeu.
+    /// * `rng` - Le générateur aléatoire de la partie.
+    /// 
+    /// # Returns
+    /// 
+    /// Une nouvelle instance d'Asteroid.
+    pub fn new(size: f32,rng:&mut impl Rng) -> Self {
+        Self::with_config(size, AsteroidConfig::default(), rng)
+    }
+
+    /// Crée une nouvelle instance d'Asteroid avec une taille et des réglages spécifiés.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `size` - La taille de l'astéroïde à créer.
+    /// * `config` - Les réglages des astéroïdes.
+    /// * `rng` - Le générateur aléatoire de la partie.
     /// 
     /// # Returns
     /// 
     /// Une nouvelle instance d'Asteroid.
-    pub fn new(size: f32,screen_size:(f32,f32)) -> Self {
+    pub fn with_config(size: f32,config:AsteroidConfig,rng:&mut impl Rng) -> Self {
+        let position = Self::new_alea_pos(config.init_size,rng);
+        let speed = Self::new_alea_speed(config.speed,rng);
+        Self::with_motion(position, speed, size, config, rng)
+    }
+
+    /// Crée un astéroïde de forme et de rotation aléatoires, à une position et une vitesse données.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `position` - La position de l'astéroïde.
+    /// * `speed` - La vitesse de l'astéroïde en unités par seconde.
+    /// * `size` - La taille de l'astéroïde.
+    /// * `config` - Les réglages des astéroïdes.
+    /// * `rng` - Le générateur aléatoire de la partie.
+    fn with_motion(position:Vec2,speed:Vec2,size:f32,config:AsteroidConfig,rng:&mut impl Rng) -> Self {
         Self {
-            position: Self::new_alea_pos(screen_size),
-            speed: Self::new_alea_speed(),
-            size, // Taille initiale de l'astéroïde
-            screen_size,
+            position,
+            previous_position: position,
+            speed,
+            size,
+            shape: Self::new_alea_shape(size,rng),
+            rotation: rng.gen_range(0.0..(2.0 * PI)),
+            spin: rng.gen_range(-Self::MAX_SPIN..=Self::MAX_SPIN),
+            config,
         }
     }
 
+    /// Génère un contour irrégulier : des sommets répartis autour de l'origine,
+    /// à une distance aléatoire proche de la taille de l'astéroïde.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `size` - La taille de l'astéroïde.
+    /// * `rng` - Le générateur aléatoire de la partie.
+    /// 
+    /// # Returns
+    /// 
+    /// Les sommets du contour, dans l'ordre.
+    fn new_alea_shape(size:f32,rng:&mut impl Rng) -> Vec<Vec2> {
+        let vertices = rng.gen_range(8..=12);
+        (0..vertices)
+            .map(|i| {
+                let angle = 2.0 * PI * i as f32 / vertices as f32;
+                Vec2::from_angle(angle) * size * rng.gen_range(0.7..=Self::OUTLINE_MAX_RADIUS)
+            })
+            .collect()
+    }
+
+    /// Retourne le contour de l'astéroïde autour de l'origine, sans rotation.
+    pub fn get_shape(&self) -> &[Vec2] {
+        &self.shape
+    }
+
+    /// Retourne la rotation actuelle de l'astéroïde en radians.
+    pub fn get_rotation(&self) -> f32 {
+        self.rotation
+    }
+
     /// Génère une position aléatoire près d'un bord de l'écran.
     /// # Arguments
     /// 
-    /// * `screen_size` - La taille de la fenetre de jeu.
+    /// * `margin` - La distance maximale au bord de l'écran.
+    /// * `rng` - Le générateur aléatoire de la partie.
     /// 
     /// # Returns
     /// 
     /// Un vecteur représentant la position aléatoire générée.
-    fn new_alea_pos(screen_size:(f32,f32)) -> Vec2 {
-        let mut rng = thread_rng();
-        let nearpos: f32 = rng.gen_range(Self::ASTEROID_INIT_SIZE / 2.0..=Self::ASTEROID_INIT_SIZE);
+    fn new_alea_pos(margin:f32,rng:&mut impl Rng) -> Vec2 {
+        let nearpos: f32 = rng.gen_range(margin / 2.0..=margin);
         let nearside = rng.gen_range(1..=4); // 1 = haut, 2 = droite, 3 = bas, 4 = gauche
         
         let xpos: f32 = match nearside {
-            2 => screen_size.0 - nearpos,
+            2 => GameWorld::SIZE.0 - nearpos,
             4 => nearpos,                   
-            _ => rng.gen_range(0.0..=screen_size.0),
+            _ => rng.gen_range(0.0..=GameWorld::SIZE.0),
         };
 
         let ypos: f32 = match nearside {
             1 => nearpos,                   
-            3 => screen_size.1 - nearpos,
-            _ => rng.gen_range(0.0..=screen_size.1),
+            3 => GameWorld::SIZE.1 - nearpos,
+            _ => rng.gen_range(0.0..=GameWorld::SIZE.1),
         };
 
         vec2(xpos, ypos)
@@ -71,49 +157,76 @@ impl Asteroid {
 
     /// Génère et retourne un vecteur de vitesse aléatoire pour l'astéroïde.
     /// 
+    /// # Arguments
+    /// 
+    /// * `speed` - La norme de la vitesse en unités par seconde.
+    /// * `rng` - Le générateur aléatoire de la partie.
+    /// 
     /// # Returns
     /// 
     /// Un vecteur représentant la vitesse aléatoire générée.
-    fn new_alea_speed() -> Vec2 {
-        let mut rng = thread_rng();
+    fn new_alea_speed(speed:f32,rng:&mut impl Rng) -> Vec2 {
         let angle: f32 = rng.gen_range(0.0..=(2.0 * PI)); 
-        Vec2::from_angle(angle) 
+        Vec2::from_angle(angle) * speed
     }
 
-    /// Vérifie et ajuste la position de l'astéroïde pour s'assurer qu'elle reste dans les limites de l'écran.
-    /// 
+    /// Retourne la vitesse de l'astéroïde en unités par seconde.
+    pub fn get_speed(&self) -> Vec2 {
+        self.speed
+    }
+
+    /// Modifie la vitesse de l'astéroïde.
+    ///
     /// # Arguments
-    /// 
-    /// * `pos` - La position à ajuster.
-    /// * `screen_size` - La taille de la fenetre de jeu.
-    /// # Returns
-    /// 
-    /// La position ajustée si elle sort des limites de l'écran.
-    fn bound_pos(mut pos: Vec2,screen_size:(f32,f32)) -> Vec2 {
-        pos.x = Self::bound_to(pos.x, screen_size.0);
-        pos.y = Self::bound_to(pos.y, screen_size.1);
-        pos
+    ///
+    /// * `speed` - La nouvelle vitesse en unités par seconde.
+    pub fn set_speed(&mut self, speed: Vec2) {
+        self.speed = speed;
     }
 
-    /// Ajuste une coordonnée pour s'assurer qu'elle reste dans les limites spécifiées.
-    /// 
+    /// Retourne la masse de l'astéroïde, proportionnelle à sa taille.
+    pub fn get_mass(&self) -> f32 {
+        self.size
+    }
+
+    /// Fait rebondir deux astéroïdes qui se touchent, par un choc élastique :
+    /// la quantité de mouvement et l'énergie cinétique sont conservées.
+    ///
+    /// Les astéroïdes qui s'éloignent déjà l'un de l'autre ne rebondissent pas,
+    /// pour qu'ils ne restent pas collés pendant qu'ils se séparent.
+    ///
     /// # Arguments
-    /// 
-    /// * `coord` - La coordonnée à ajuster.
-    /// * `max` - La valeur maximale de la coordonnée.
-    /// 
+    ///
+    /// * `other` - L'autre astéroïde.
+    ///
     /// # Returns
-    /// 
-    /// La coordonnée ajustée.
-    fn bound_to(coord: f32, max: f32) -> f32 {
-        if coord < 0.0 {
-            max - coord 
-        } else if coord > max {
-            coord - max 
-        } else {
-            coord 
+    ///
+    /// La vitesse relative des deux astéroïdes au moment du choc, ou `None` s'ils ne
+    /// rebondissent pas.
+    pub fn bounce(&mut self, other: &mut Asteroid) -> Option<f32> {
+        // Test rapide sur les cercles englobants avant de comparer les contours,
+        // l'autre astéroïde étant vu du côté du bord le plus proche
+        let reach = (self.size + other.size) * Self::OUTLINE_MAX_RADIUS;
+        let offset = torus::displacement(self.position, other.position, GameWorld::SIZE);
+        if offset.length() >= reach {
+            return None;
+        }
+        let other_outline = torus::outline_near(other.get_outline(), other.position, self.position, GameWorld::SIZE);
+        if !polygon::polygons_intersect(&self.get_outline(), &other_outline) {
+            return None;
+        }
+        let normal = offset.try_normalize()?;
+        let closing = (self.speed - other.speed).dot(normal);
+        if closing <= 0.0 {
+            return None;
         }
+        let (m1, m2) = (self.get_mass(), other.get_mass());
+        let impulse = 2.0 * closing / (1.0 / m1 + 1.0 / m2);
+        self.speed -= normal * impulse / m1;
+        other.speed += normal * impulse / m2;
+        Some(closing)
     }
+
     /// Modif la position de l'asteroid 
     /// # Arguments
     /// 
@@ -121,35 +234,47 @@ impl Asteroid {
     /// 
     pub fn set_position(&mut self,new_pos:Vec2){
         self.position=new_pos;
+        self.previous_position=new_pos;
     }
-    /// Divise un astéroïde en deux nouveaux astéroïdes plus petits.
+    /// Divise un astéroïde en fragments plus petits.
+    ///
+    /// Les fragments apparaissent à l'intérieur de l'astéroïde d'origine et gardent sa vitesse,
+    /// à laquelle s'ajoute une poussée de `asteroid.split_speed` répartie en éventail autour
+    /// de la direction de l'impact : les poussées latérales s'annulent, et la quantité de
+    /// mouvement de l'astéroïde est conservée à la poussée de l'impact près.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `impact` - La direction de l'impact, par exemple la vitesse du missile.
+    /// * `rng` - Le générateur aléatoire de la partie.
     /// 
     /// # Returns
     /// 
-    /// Une option contenant les deux nouveaux astéroïdes ou `None` si l'astéroïde ne peut pas être divisé.
-    pub fn split_asteroid(&self) -> Option<(Self, Self)> {
-        let position=self.get_position();
-        
-        let new_size=match self.size {
-            Self::ASTEROID_INIT_SIZE => {
-                Some(Self::ASTEROID_MEDIUM_SIZE)
-            },
-            Self::ASTEROID_MEDIUM_SIZE => {
-                Some(Self::ASTEROID_SMALL_SIZE)
-            },
-            _ => None, // Pas de division possible pour les petits astéroïdes
+    /// Les fragments, `asteroid.fragments_large` ou `asteroid.fragments_medium` selon la taille,
+    /// ou aucun pour les petits astéroïdes qui ne se divisent plus.
+    pub fn split_asteroid(&self,impact:Vec2,rng:&mut impl Rng) -> Vec<Self> {
+        // Pas de division possible pour les petits astéroïdes
+        let Some(new_size) = self.config.split_size(self.size) else {
+            return Vec::new();
         };
-        match new_size{
-            Some(s)=>{
-                let mut a1= Asteroid::new(s,self.screen_size);
-                let mut a2 =Asteroid::new(s,self.screen_size);
-                a1.set_position(position+vec2(50.0,50.0));
-                a2.set_position(position-vec2(50.0,-50.0));
-                Some((a1,a2))
-            },
-            None=> None , 
-        }
-
+        let count = self.config.fragment_count(self.size);
+        // Sans direction d'impact, les fragments partent dans une direction au hasard
+        let direction = impact.try_normalize().unwrap_or_else(|| Self::new_alea_speed(1.0, rng));
+        // Léger désordre pour que deux divisions ne se ressemblent pas
+        let jitter = rng.gen_range(-Self::SPLIT_JITTER..=Self::SPLIT_JITTER);
+        (0..count)
+            .map(|i| {
+                let angle = if count > 1 {
+                    -Self::SPLIT_SPREAD + 2.0 * Self::SPLIT_SPREAD * i as f32 / (count - 1) as f32
+                } else {
+                    0.0
+                };
+                let kick = Vec2::from_angle(angle + jitter).rotate(direction);
+                let position = torus::wrap(self.position + kick * (self.size - new_size), GameWorld::SIZE);
+                let speed = self.speed + kick * self.config.split_speed;
+                Self::with_motion(position, speed, new_size, self.config, rng)
+            })
+            .collect()
     }
 }
 
@@ -164,12 +289,24 @@ impl StellarObject for Asteroid{
     fn get_position(&self) -> Vec2 {
         self.position
     }
-    /// Met à jour la position de l'astéroïde en fonction de sa vitesse.
+
+    /// Retourne la position de l'astéroïde au pas précédent.
+    fn get_previous_position(&self) -> Vec2 {
+        self.previous_position
+    }
+
+    /// Met à jour la position et la rotation de l'astéroïde en fonction de sa vitesse.
     /// 
-    fn update(&mut self) {
-        self.position += self.speed; // Mise à jour de la position
-        self.position = Self::bound_pos(self.position,self.screen_size); // Vérification des limites
-        
+    /// # Arguments
+    /// 
+    /// * `dt` - La durée du pas de simulation en secondes.
+    fn update(&mut self, dt: f32) {
+        self.rotation = (self.rotation + self.spin * dt).rem_euclid(2.0 * PI);
+        self.previous_position = self.position;
+        let moved = self.position + self.speed * dt; // Mise à jour de la position
+        self.position = torus::wrap(moved, GameWorld::SIZE); // Retour de l'autre côté de l'écran
+        // Décale aussi la position précédente pour que l'interpolation ne traverse pas l'écran
+        self.previous_position += self.position - moved;
     }
     
     /// Retourne la taille actuelle de l'astéroïde.
@@ -181,7 +318,108 @@ impl StellarObject for Asteroid{
         self.size
     }
 
+    /// Retourne le contour irrégulier de l'astéroïde à l'écran.
+    fn get_outline(&self) -> Vec<Vec2> {
+        polygon::transform(&self.shape, self.position, self.rotation)
+    }
+
 }
 
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::rand::{rngs::SmallRng, SeedableRng};
 
+    #[test]
+    fn test_split_fragments() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut asteroid = Asteroid::new(Asteroid::ASTEROID_INIT_SIZE, &mut rng);
+        asteroid.set_position(vec2(400.0, 300.0));
 
+        let impact = vec2(0.0, -600.0);
+        let fragments = asteroid.split_asteroid(impact, &mut rng);
+
+        assert_eq!(fragments.len(), AsteroidConfig::default().fragments_large as usize);
+        let config = AsteroidConfig::default();
+        let mut momentum = Vec2::ZERO;
+        for fragment in &fragments {
+            assert_eq!(fragment.get_size(), Asteroid::ASTEROID_MEDIUM_SIZE);
+            // Les fragments restent à l'intérieur de l'astéroïde d'origine
+            assert!(fragment.get_position().distance(asteroid.get_position()) <= asteroid.get_size());
+            momentum += fragment.speed - asteroid.speed;
+        }
+        // Les poussées s'annulent sur les côtés et poussent dans le sens de l'impact
+        let along = momentum.dot(impact.normalize());
+        assert!(along > 0.0 && along <= config.split_speed * fragments.len() as f32);
+        assert!((momentum - along * impact.normalize()).length() < along);
+    }
+
+    #[test]
+    fn test_wraps_at_same_distance_from_edge() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut asteroid = Asteroid::new(Asteroid::ASTEROID_SMALL_SIZE, &mut rng);
+        asteroid.set_position(vec2(1.0, 300.0));
+        asteroid.set_speed(vec2(-60.0, 0.0));
+
+        asteroid.update(0.5);
+
+        // Sorti de 29 à gauche, il réapparaît à 29 du bord droit
+        assert!(asteroid.get_position().abs_diff_eq(vec2(771.0, 300.0), 1e-3));
+    }
+
+    #[test]
+    fn test_irregular_outline() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut asteroid = Asteroid::new(Asteroid::ASTEROID_INIT_SIZE, &mut rng);
+        asteroid.set_position(vec2(400.0, 300.0));
+
+        let outline = asteroid.get_outline();
+        assert!((8..=12).contains(&outline.len()));
+        for point in &outline {
+            let distance = point.distance(asteroid.get_position());
+            assert!(distance >= 0.7 * asteroid.get_size() - 1e-3 && distance <= 1.1 * asteroid.get_size() + 1e-3);
+        }
+        assert!(polygon::contains_point(&outline, asteroid.get_position()));
+
+        // L'astéroïde tourne sur lui-même
+        let rotation = asteroid.get_rotation();
+        asteroid.update(1.0);
+        assert!(asteroid.get_rotation() != rotation || asteroid.spin == 0.0);
+    }
+
+    #[test]
+    fn test_elastic_bounce() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut large = Asteroid::new(Asteroid::ASTEROID_INIT_SIZE, &mut rng);
+        let mut small = Asteroid::new(Asteroid::ASTEROID_SMALL_SIZE, &mut rng);
+        large.set_position(vec2(400.0, 300.0));
+        small.set_position(vec2(440.0, 300.0));
+        large.speed = vec2(30.0, 0.0);
+        small.speed = vec2(-60.0, 10.0);
+        let momentum = |a: &Asteroid, b: &Asteroid| a.speed * a.get_mass() + b.speed * b.get_mass();
+        let energy = |a: &Asteroid, b: &Asteroid| a.get_mass() * a.speed.length_squared() + b.get_mass() * b.speed.length_squared();
+        let (p, e) = (momentum(&large, &small), energy(&large, &small));
+
+        assert_eq!(large.bounce(&mut small), Some(90.0));
+
+        assert!(momentum(&large, &small).abs_diff_eq(p, 1e-3));
+        assert!((energy(&large, &small) - e).abs() < 1e-2 * e);
+        // Le petit astéroïde repart dans l'autre sens, et ne rebondit pas une seconde fois
+        assert!(small.speed.x > 0.0);
+        assert_eq!(large.bounce(&mut small), None);
+    }
+
+    #[test]
+    fn test_fragment_count_per_tier() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let config = AsteroidConfig { fragments_large: 3, fragments_medium: 4, ..AsteroidConfig::default() };
+        let large = Asteroid::with_config(config.init_size, config, &mut rng);
+        let medium = Asteroid::with_config(config.medium_size, config, &mut rng);
+        let small = Asteroid::with_config(config.small_size, config, &mut rng);
+
+        assert_eq!(large.split_asteroid(Vec2::X, &mut rng).len(), 3);
+        assert_eq!(medium.split_asteroid(Vec2::X, &mut rng).len(), 4);
+        assert!(small.split_asteroid(Vec2::X, &mut rng).is_empty());
+    }
+}
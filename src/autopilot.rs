@@ -0,0 +1,395 @@
+//! Pilote automatique : une [`InputSource`] qui joue la partie.
+//!
+//! A chaque décision, le pilote cherche l'objet qui menace le plus le vaisseau (celui
+//! qui passera le plus tôt à portée de collision), l'esquive s'il est trop proche,
+//! et sinon vise l'astéroïde le plus menaçant, ou le plus proche si rien ne menace,
+//! en anticipant son déplacement pendant le trajet du missile. Le niveau ([`Skill`])
+//! règle le temps de réaction, la précision du tir et la distance d'anticipation.
+//!
+//! Le pilote ne lit que ce que montre [`GameWorld`] et son hasard est tiré d'une graine :
+//! une partie jouée par le pilote est reproductible, comme un replay.
+use std::f32::consts::{FRAC_PI_2, PI};
+use macroquad::prelude::*;
+use ::rand::{Rng, SeedableRng};
+use rand_xoshiro::Xoshiro256PlusPlus;
+use crate::asteroid::Asteroid;
+use crate::input::InputSource;
+use crate::spaceship::Spaceship;
+use crate::stellarobject::StellarObject;
+use crate::torus;
+use crate::world::{GameWorld, PlayerInput};
+
+/// Niveau du pilote automatique.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Skill {
+    /// Réagit lentement, vise mal et ne voit le danger qu'au dernier moment.
+    Beginner,
+    /// Un joueur correct.
+    #[default]
+    Intermediate,
+    /// Décide à chaque pas et vise juste.
+    Expert,
+}
+
+impl Skill {
+    /// Tous les niveaux, du plus faible au plus fort.
+    pub const ALL: [Skill; 3] = [Skill::Beginner, Skill::Intermediate, Skill::Expert];
+
+    /// Nombre de pas entre deux décisions : entre les deux, la commande est maintenue.
+    fn reaction_ticks(self) -> u64 {
+        match self {
+            Skill::Beginner => 15,
+            Skill::Intermediate => 6,
+            Skill::Expert => 1,
+        }
+    }
+
+    /// Erreur de visée maximale, en radians.
+    fn aim_error(self) -> f32 {
+        match self {
+            Skill::Beginner => 0.2,
+            Skill::Intermediate => 0.06,
+            Skill::Expert => 0.0,
+        }
+    }
+
+    /// Horizon d'anticipation des collisions, en secondes.
+    fn lookahead(self) -> f32 {
+        match self {
+            Skill::Beginner => 0.4,
+            Skill::Intermediate => 0.8,
+            Skill::Expert => 1.2,
+        }
+    }
+
+    /// Retourne le nom du niveau, tel qu'il s'écrit sur la ligne de commande.
+    pub fn name(self) -> &'static str {
+        match self {
+            Skill::Beginner => "beginner",
+            Skill::Intermediate => "intermediate",
+            Skill::Expert => "expert",
+        }
+    }
+
+    /// Retrouve un niveau d'après son nom.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Le nom du niveau (`beginner`, `intermediate` ou `expert`).
+    pub fn from_name(name: &str) -> Option<Skill> {
+        Self::ALL.into_iter().find(|skill| skill.name() == name)
+    }
+
+    /// Indique si le pilote se sauve en hyperespace quand une collision est inévitable.
+    fn uses_hyperspace(self) -> bool {
+        self != Skill::Beginner
+    }
+}
+
+/// Vitesse au-delà de laquelle le pilote freine quand il n'esquive rien, en unités par seconde.
+const CRUISE_SPEED: f32 = 120.0;
+/// Marge ajoutée aux rayons pour juger qu'un objet menace le vaisseau.
+const SAFETY_MARGIN: f32 = 20.0;
+/// Délai avant collision en dessous duquel le pilote esquive plutôt que de viser, en secondes.
+const DODGE_TIME: f32 = 0.6;
+/// Délai avant collision en dessous duquel l'esquive ne suffit plus, en secondes.
+const JUMP_TIME: f32 = 0.1;
+/// Rayon prêté aux missiles ennemis, qui sont des points.
+const MISSILE_RADIUS: f32 = 2.0;
+
+/// Un objet que le pilote surveille, vu depuis le vaisseau.
+#[derive(Clone, Copy, Debug)]
+struct Contact {
+    offset: Vec2,   // Position relative au vaisseau, par le plus court chemin
+    velocity: Vec2, // Vitesse en unités par seconde
+    radius: f32,    // Rayon de collision
+    asteroid: bool, // Seuls les astéroïdes sont des cibles
+}
+
+impl Contact {
+    /// Relève un objet de la partie.
+    ///
+    /// # Arguments
+    ///
+    /// * `object` - L'objet.
+    /// * `radius` - Son rayon de collision.
+    /// * `asteroid` - Vrai pour un astéroïde.
+    /// * `world` - La partie.
+    fn new(object: &dyn StellarObject, radius: f32, asteroid: bool, world: &GameWorld) -> Self {
+        let ship = world.spaceship().get_position();
+        let screen_size = world.screen_size();
+        // La vitesse est déduite du dernier déplacement, valable pour tous les objets
+        let moved = torus::displacement(object.get_previous_position(), object.get_position(), screen_size);
+        Self {
+            offset: torus::displacement(ship, object.get_position(), screen_size),
+            velocity: moved / GameWorld::TICK,
+            radius,
+            asteroid,
+        }
+    }
+
+    /// Retourne l'instant où l'objet passe au plus près du vaisseau, dans l'horizon
+    /// donné, et la distance entre leurs centres à cet instant.
+    ///
+    /// # Arguments
+    ///
+    /// * `ship_velocity` - La vitesse du vaisseau.
+    /// * `horizon` - L'horizon d'anticipation en secondes.
+    fn closest_approach(&self, ship_velocity: Vec2, horizon: f32) -> (f32, f32) {
+        let relative = self.velocity - ship_velocity;
+        let speed2 = relative.length_squared();
+        let time = if speed2 > 0.0 { (-self.offset.dot(relative) / speed2).clamp(0.0, horizon) } else { 0.0 };
+        (time, (self.offset + relative * time).length())
+    }
+}
+
+/// Retourne le point de rencontre entre un missile tiré maintenant et une cible
+/// en mouvement rectiligne.
+///
+/// # Arguments
+///
+/// * `offset` - La position de la cible par rapport au tireur.
+/// * `velocity` - La vitesse de la cible par rapport au missile au départ.
+/// * `missile_speed` - La vitesse propre du missile ([`Missile::SPEED`](crate::Missile::SPEED) par défaut).
+///
+/// # Returns
+///
+/// La position de la rencontre par rapport au tireur, et le temps de vol du missile,
+/// ou `None` si le missile ne rattrape jamais la cible.
+fn intercept(offset: Vec2, velocity: Vec2, missile_speed: f32) -> Option<(Vec2, f32)> {
+    // |offset + velocity t| = missile_speed t
+    let a = velocity.length_squared() - missile_speed * missile_speed;
+    let b = 2.0 * offset.dot(velocity);
+    let c = offset.length_squared();
+    let time = if a.abs() < f32::EPSILON {
+        if b >= 0.0 { return None; }
+        -c / b
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let root = discriminant.sqrt();
+        // La plus petite solution positive
+        [(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)]
+            .into_iter()
+            .filter(|&t| t > 0.0)
+            .min_by(|x, y| x.total_cmp(y))?
+    };
+    Some((offset + velocity * time, time))
+}
+
+/// Retourne l'orientation du vaisseau qui pointe dans une direction : 0 vers le haut,
+/// dans le sens des aiguilles d'une montre, comme [`Spaceship::get_orientation`].
+fn heading(direction: Vec2) -> f32 {
+    direction.x.atan2(-direction.y)
+}
+
+/// Ramène un angle dans `[-PI, PI]`.
+fn normalize_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(2.0 * PI) - PI
+}
+
+/// Retourne l'angle dont tourne le vaisseau en un pas de simulation.
+fn ship_rotation_step(world: &GameWorld) -> f32 {
+    world.config().ship.rotation_speed * GameWorld::TICK
+}
+
+/// Pilote automatique.
+#[derive(Clone, Debug)]
+pub struct Autopilot {
+    skill: Skill,
+    rng: Xoshiro256PlusPlus, // Erreurs de visée, tirées d'une graine pour que la partie soit reproductible
+    input: PlayerInput,      // Commande maintenue jusqu'à la prochaine décision
+    rotate_ticks: u64,       // Pas de rotation restants avant d'atteindre l'orientation voulue
+    wait: u64,               // Pas restants avant la prochaine décision
+}
+
+impl Autopilot {
+    /// Crée un pilote automatique.
+    ///
+    /// # Arguments
+    ///
+    /// * `skill` - Le niveau du pilote.
+    /// * `seed` - La graine de ses erreurs de visée.
+    pub fn new(skill: Skill, seed: u64) -> Self {
+        Self {
+            skill,
+            rng: Xoshiro256PlusPlus::seed_from_u64(seed),
+            input: PlayerInput::default(),
+            rotate_ticks: 0,
+            wait: 0,
+        }
+    }
+
+    /// Retourne le niveau du pilote.
+    pub fn skill(&self) -> Skill {
+        self.skill
+    }
+
+    /// Relève les objets qui peuvent toucher le vaisseau.
+    fn contacts(world: &GameWorld) -> Vec<Contact> {
+        let asteroids = world.asteroids().iter()
+            .map(|a| Contact::new(a, a.get_size() * Asteroid::OUTLINE_MAX_RADIUS, true, world));
+        let saucers = world.saucers().iter().map(|s| Contact::new(s, s.get_size(), false, world));
+        let missiles = world.enemy_missiles().iter().map(|m| Contact::new(m, MISSILE_RADIUS, false, world));
+        asteroids.chain(saucers).chain(missiles).collect()
+    }
+
+    /// Prend une décision à partir de l'état de la partie.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - La partie.
+    ///
+    /// # Returns
+    ///
+    /// La commande à appliquer, et l'orientation visée.
+    fn decide(&mut self, world: &GameWorld) -> (PlayerInput, f32) {
+        let ship = world.spaceship();
+        let ship_velocity = ship.get_speed();
+        let orientation = ship.get_orientation();
+        let contacts = Self::contacts(world);
+        let mut input = PlayerInput::default();
+
+        // La menace la plus urgente : l'objet qui entrera le plus tôt en collision
+        let horizon = self.skill.lookahead();
+        let threat = contacts.iter()
+            .filter_map(|c| {
+                let (time, distance) = c.closest_approach(ship_velocity, horizon);
+                (distance < c.radius + Spaceship::RADIUS + SAFETY_MARGIN).then_some((c, time))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((contact, time)) = threat.filter(|&(_, time)| time < DODGE_TIME) {
+            if time < JUMP_TIME && self.skill.uses_hyperspace() && ship.can_jump() {
+                input.hyperspace = true;
+                return (input, orientation);
+            }
+            // S'écarter de la trajectoire de l'objet, en s'éloignant du point où il passera
+            let relative = contact.velocity - ship_velocity;
+            let closest = contact.offset + relative * time;
+            let away = if closest.length() > 1.0 { -closest } else { relative.perp() };
+            let target = heading(away);
+            input.thrust = normalize_angle(target - orientation).abs() < FRAC_PI_2;
+            return (input, target);
+        }
+
+        // Cible : l'astéroïde qui menace, sinon le plus proche
+        let target = match threat {
+            Some((contact, _)) if contact.asteroid => Some(*contact),
+            _ => contacts.iter().filter(|c| c.asteroid).min_by(|a, b| {
+                a.offset.length_squared().total_cmp(&b.offset.length_squared())
+            }).copied(),
+        };
+        let missile = world.config().missile;
+        let mut aim = orientation;
+        if let Some(target) = target {
+            // Un missile qui emporte la vitesse du vaisseau voit la cible se déplacer moins vite
+            let velocity = if missile.inherit_velocity { target.velocity - ship_velocity } else { target.velocity };
+            let (point, flight) = intercept(target.offset, velocity, missile.speed).unwrap_or((target.offset, f32::INFINITY));
+            let error = self.skill.aim_error();
+            aim = heading(point) + if error > 0.0 { self.rng.gen_range(-error..=error) } else { 0.0 };
+            // Tirer quand la cible est dans l'axe, à portée et que l'arme est prête
+            let tolerance = (target.radius / point.length().max(1.0)).atan().max(ship_rotation_step(world));
+            input.fire = normalize_angle(aim - orientation).abs() <= tolerance
+                && flight < missile.lifetime
+                && world.weapon().can_fire(world.missiles().len());
+        }
+        input.back_thrust = ship_velocity.length() > CRUISE_SPEED;
+        (input, aim)
+    }
+}
+
+impl InputSource for Autopilot {
+    fn next_input(&mut self, world: &GameWorld) -> Option<PlayerInput> {
+        if !world.spaceship().is_visible() {
+            self.wait = 0;
+            self.input = PlayerInput::default();
+            return Some(self.input);
+        }
+        if self.wait > 0 {
+            // Entre deux décisions la commande est maintenue, mais le tir et le saut ne
+            // se déclenchent qu'à l'appui : les relâcher
+            self.wait -= 1;
+            self.input.fire = false;
+            self.input.hyperspace = false;
+            if self.rotate_ticks == 0 {
+                self.input.rotate_left = false;
+                self.input.rotate_right = false;
+            }
+            self.rotate_ticks = self.rotate_ticks.saturating_sub(1);
+            return Some(self.input);
+        }
+
+        let (mut input, target) = self.decide(world);
+        let turn = normalize_angle(target - world.spaceship().get_orientation());
+        let step = ship_rotation_step(world);
+        // Tourner du nombre de pas nécessaires, sans dépasser l'orientation voulue
+        self.rotate_ticks = (turn.abs() / step).round() as u64;
+        if self.rotate_ticks > 0 {
+            input.rotate_right = turn > 0.0;
+            input.rotate_left = turn < 0.0;
+            self.rotate_ticks -= 1;
+        }
+        // Le tir ne part qu'à l'appui : relâcher si le pas précédent tirait déjà
+        input.fire &= !self.input.fire;
+        self.input = input;
+        self.wait = self.skill.reaction_ticks() - 1;
+        Some(input)
+    }
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input;
+
+    #[test]
+    fn test_intercept_leads_target() {
+        // Cible à 300 devant, qui file vers la droite à 100 unités par seconde
+        let (point, time) = intercept(vec2(0.0, -300.0), vec2(100.0, 0.0), 600.0).unwrap();
+
+        assert!(point.x > 0.0);
+        assert!((point.length() - 600.0 * time).abs() < 1e-3);
+        assert!((point - (vec2(0.0, -300.0) + vec2(100.0, 0.0) * time)).length() < 1e-3);
+        // Une cible plus rapide que le missile qui s'éloigne n'est jamais rattrapée
+        assert!(intercept(vec2(0.0, -300.0), vec2(0.0, -700.0), 600.0).is_none());
+    }
+
+    #[test]
+    fn test_heading_matches_ship_orientation() {
+        assert!(heading(vec2(0.0, -1.0)).abs() < 1e-6);
+        assert!((heading(vec2(1.0, 0.0)) - FRAC_PI_2).abs() < 1e-6);
+        assert!((normalize_angle(3.0 * PI / 2.0) + FRAC_PI_2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_autopilot_is_reproducible() {
+        let play = || {
+            let mut world = GameWorld::new(3);
+            let mut autopilot = Autopilot::new(Skill::Intermediate, 3);
+            input::run(&mut world, &mut autopilot, 600);
+            (world.ticks(), world.score(), world.spaceship().get_position())
+        };
+
+        assert_eq!(play(), play());
+    }
+
+    #[test]
+    fn test_skill_levels_rank() {
+        // Score moyen sur quelques parties d'une minute
+        let score = |skill: Skill| -> u32 {
+            (0..4).map(|seed| {
+                let mut world = GameWorld::new(seed);
+                input::run(&mut world, &mut Autopilot::new(skill, seed), 3600);
+                world.score()
+            }).sum()
+        };
+        let scores: Vec<_> = Skill::ALL.into_iter().map(score).collect();
+
+        assert!(scores[0] > 0);
+        assert!(scores[0] < scores[1] && scores[1] < scores[2], "{:?}", scores);
+    }
+}
@@ -0,0 +1,637 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::asteroid::Asteroid;
+use crate::missile::Missile;
+use crate::spaceship::Spaceship;
+
+/// Réglages du vaisseau (distances en unités, durées en secondes).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShipConfig {
+    pub max_speed: f32,
+    pub min_speed: f32,
+    pub acceleration: f32,
+    /// Vitesse de rotation en radians par seconde.
+    pub rotation_speed: f32,
+    /// Fraction de la vitesse conservée au bout d'une seconde.
+    pub friction: f32,
+    pub initial_shield: i32,
+    /// Durée d'invulnérabilité après une collision, en secondes.
+    pub invulnerability_time: f32,
+    /// Rayon autour du centre qui doit être libre d'astéroïdes pour réapparaître
+    /// après une collision (0 pour réapparaître immédiatement).
+    pub respawn_clear_radius: f32,
+    /// Durée pendant laquelle le vaisseau disparaît lors d'un saut en hyperespace.
+    pub hyperspace_time: f32,
+    /// Durée minimale entre deux sauts en hyperespace.
+    pub hyperspace_cooldown: f32,
+    /// Probabilité de réapparaître dans un astéroïde après un saut (entre 0 et 1).
+    pub hyperspace_risk: f64,
+}
+
+impl Default for ShipConfig {
+    fn default() -> Self {
+        Self {
+            max_speed: Spaceship::MAX_SPEED,
+            min_speed: Spaceship::MIN_SPEED,
+            acceleration: Spaceship::ACCELERATION,
+            rotation_speed: Spaceship::ROTATION_SPEED,
+            friction: Spaceship::FRICTION,
+            initial_shield: Spaceship::INITIAL_SHIELD,
+            invulnerability_time: Spaceship::INVULNERABILITY_TIME,
+            respawn_clear_radius: Spaceship::RESPAWN_CLEAR_RADIUS,
+            hyperspace_time: Spaceship::HYPERSPACE_TIME,
+            hyperspace_cooldown: Spaceship::HYPERSPACE_COOLDOWN,
+            hyperspace_risk: Spaceship::HYPERSPACE_RISK,
+        }
+    }
+}
+
+/// Réglages des missiles.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MissileConfig {
+    /// Vitesse des missiles en unités par seconde.
+    pub speed: f32,
+    /// Durée de vie des missiles en secondes : leur portée vaut `speed * lifetime`.
+    pub lifetime: f32,
+    /// Ajoute la vitesse du vaisseau à celle des missiles qu'il tire.
+    pub inherit_velocity: bool,
+}
+
+impl Default for MissileConfig {
+    fn default() -> Self {
+        Self { speed: Missile::SPEED, lifetime: Missile::LIFETIME, inherit_velocity: false }
+    }
+}
+
+/// Réglages de l'arme du vaisseau (durées en secondes).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WeaponConfig {
+    /// Durée minimale entre deux tirs.
+    pub cooldown: f32,
+    /// Nombre maximal de missiles du joueur en vol en même temps.
+    pub max_missiles: u32,
+    /// Chaleur ajoutée par chaque tir, l'arme surchauffant à 1 (0 pour désactiver la surchauffe).
+    pub heat_per_shot: f32,
+    /// Chaleur dissipée par seconde.
+    pub cooling_rate: f32,
+}
+
+impl Default for WeaponConfig {
+    fn default() -> Self {
+        Self {
+            cooldown: 0.2,
+            max_missiles: 8,
+            heat_per_shot: 0.1,
+            cooling_rate: 0.3,
+        }
+    }
+}
+
+/// Réglages des astéroïdes.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AsteroidConfig {
+    pub init_size: f32,
+    pub medium_size: f32,
+    pub small_size: f32,
+    /// Vitesse des astéroïdes en unités par seconde.
+    pub speed: f32,
+    /// Nombre minimal d'astéroïdes en début de partie.
+    pub min_count: u32,
+    /// Nombre maximal d'astéroïdes en début de partie.
+    pub max_count: u32,
+    /// Nombre de fragments d'un astéroïde de taille `init_size` détruit.
+    pub fragments_large: u32,
+    /// Nombre de fragments d'un astéroïde de taille `medium_size` détruit.
+    pub fragments_medium: u32,
+    /// Vitesse ajoutée aux fragments, en éventail autour de la direction de l'impact.
+    pub split_speed: f32,
+    /// Les astéroïdes rebondissent les uns sur les autres, avec une masse proportionnelle à leur taille.
+    pub bounce: bool,
+    /// Lors d'un rebond, le plus petit astéroïde se divise si la vitesse du choc
+    /// dépasse `chip_speed`.
+    pub chip: bool,
+    /// Vitesse relative minimale d'un choc qui divise le plus petit astéroïde.
+    pub chip_speed: f32,
+}
+
+impl Default for AsteroidConfig {
+    fn default() -> Self {
+        Self {
+            init_size: Asteroid::ASTEROID_INIT_SIZE,
+            medium_size: Asteroid::ASTEROID_MEDIUM_SIZE,
+            small_size: Asteroid::ASTEROID_SMALL_SIZE,
+            speed: Asteroid::ASTEROID_SPEED,
+            min_count: 4,
+            max_count: 8,
+            fragments_large: 2,
+            fragments_medium: 2,
+            split_speed: 40.0,
+            bounce: false,
+            chip: false,
+            chip_speed: 100.0,
+        }
+    }
+}
+
+impl AsteroidConfig {
+    /// Plus grande valeur acceptée pour `max_count`, pour qu'une faute de frappe ne
+    /// fasse pas créer des millions d'astéroïdes.
+    pub const MAX_COUNT: u32 = 100;
+    /// Plus grand nombre de fragments accepté pour `fragments_large` et `fragments_medium`.
+    pub const MAX_FRAGMENTS: u32 = 8;
+
+    /// Retourne la taille des fragments d'un astéroïde divisé.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - La taille de l'astéroïde divisé.
+    ///
+    /// # Returns
+    ///
+    /// La taille des fragments, ou `None` pour les petits astéroïdes qui ne se divisent plus.
+    pub fn split_size(&self, size: f32) -> Option<f32> {
+        if size == self.init_size {
+            Some(self.medium_size)
+        } else if size == self.medium_size {
+            Some(self.small_size)
+        } else {
+            None
+        }
+    }
+
+    /// Retourne le nombre de fragments d'un astéroïde divisé.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - La taille de l'astéroïde divisé.
+    ///
+    /// # Returns
+    ///
+    /// Le nombre de fragments de sa catégorie de taille, 0 pour les petits astéroïdes.
+    pub fn fragment_count(&self, size: f32) -> u32 {
+        if size == self.init_size {
+            self.fragments_large
+        } else if size == self.medium_size {
+            self.fragments_medium
+        } else {
+            0
+        }
+    }
+}
+
+/// Réglages des soucoupes volantes ennemies (distances en unités, durées en secondes).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SaucerConfig {
+    /// Rayon de la grande soucoupe.
+    pub large_size: f32,
+    /// Rayon de la petite soucoupe.
+    pub small_size: f32,
+    /// Vitesse horizontale des soucoupes en unités par seconde.
+    pub speed: f32,
+    /// Vitesse des missiles ennemis en unités par seconde.
+    pub missile_speed: f32,
+    /// Durée de vie des missiles ennemis en secondes.
+    pub missile_lifetime: f32,
+    /// Durée entre deux tirs d'une soucoupe.
+    pub fire_interval: f32,
+    /// Durée moyenne entre deux apparitions de soucoupe.
+    pub spawn_interval: f32,
+    /// Probabilité qu'une soucoupe soit petite (entre 0 et 1).
+    pub small_chance: f64,
+    /// Imprécision maximale du tir de la petite soucoupe, en radians.
+    pub aim_error: f32,
+}
+
+impl Default for SaucerConfig {
+    fn default() -> Self {
+        Self {
+            large_size: 20.0,
+            small_size: 10.0,
+            speed: 100.0,
+            missile_speed: 300.0,
+            missile_lifetime: 2.0,
+            fire_interval: 1.5,
+            spawn_interval: 15.0,
+            small_chance: 0.3,
+            aim_error: 0.1,
+        }
+    }
+}
+
+/// Réglages des bonus lâchés par les astéroïdes détruits (distances en unités, durées en secondes).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PowerUpConfig {
+    /// Probabilité qu'un astéroïde détruit au missile lâche un bonus (entre 0 et 1).
+    pub drop_chance: f64,
+    /// Rayon d'un bonus.
+    pub size: f32,
+    /// Vitesse de dérive d'un bonus en unités par seconde.
+    pub speed: f32,
+    /// Durée pendant laquelle un bonus peut être ramassé.
+    pub lifetime: f32,
+    /// Durée des effets du tir multiple, du tir rapide et des missiles perçants.
+    pub duration: f32,
+    /// Niveau de bouclier maximal atteignable par recharge.
+    pub max_shield: i32,
+    /// Ecart d'angle entre les missiles du tir multiple, en radians.
+    pub multi_shot_spread: f32,
+    /// Durée entre deux tirs automatiques du tir rapide.
+    pub rapid_fire_interval: f32,
+}
+
+impl Default for PowerUpConfig {
+    fn default() -> Self {
+        Self {
+            drop_chance: 0.1,
+            size: 10.0,
+            speed: 30.0,
+            lifetime: 8.0,
+            duration: 10.0,
+            max_shield: 5,
+            multi_shot_spread: 0.2,
+            rapid_fire_interval: 0.15,
+        }
+    }
+}
+
+/// Points gagnés en détruisant un astéroïde ou une soucoupe au missile.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScoreConfig {
+    /// Points d'un astéroïde de taille `asteroid.init_size`.
+    pub large: u32,
+    /// Points d'un astéroïde de taille `asteroid.medium_size`.
+    pub medium: u32,
+    /// Points d'un astéroïde de taille `asteroid.small_size`.
+    pub small: u32,
+    /// Points de la grande soucoupe.
+    pub large_saucer: u32,
+    /// Points de la petite soucoupe.
+    pub small_saucer: u32,
+}
+
+impl Default for ScoreConfig {
+    fn default() -> Self {
+        Self { large: 20, medium: 50, small: 100, large_saucer: 200, small_saucer: 1000 }
+    }
+}
+
+/// Réglages de la progression par vagues.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WaveConfig {
+    /// Nombre d'astéroïdes ajoutés à chaque nouvelle vague.
+    pub extra_asteroids: u32,
+    /// Facteur appliqué à la vitesse des astéroïdes à chaque nouvelle vague.
+    pub speed_factor: f32,
+    /// Vitesse maximale des astéroïdes, quelle que soit la vague, en unités par seconde.
+    pub max_speed: f32,
+    /// Nombre maximal d'astéroïdes au début d'une vague.
+    pub max_count: u32,
+    /// Durée de l'écran de transition entre deux vagues, en secondes.
+    pub transition_time: f32,
+}
+
+impl Default for WaveConfig {
+    fn default() -> Self {
+        Self {
+            extra_asteroids: 1,
+            speed_factor: 1.1,
+            max_speed: 240.0,
+            max_count: 30,
+            transition_time: 2.0,
+        }
+    }
+}
+
+/// Ensemble des réglages du jeu, chargés depuis un fichier TOML.
+///
+/// Toutes les sections et tous les champs sont facultatifs : une valeur absente
+/// garde sa valeur par défaut, celle des constantes de [`Spaceship`], [`Missile`] et [`Asteroid`].
+///
+/// ```toml
+/// [ship]
+/// acceleration = 600.0
+///
+/// [asteroid]
+/// min_count = 4
+/// max_count = 8
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    pub ship: ShipConfig,
+    pub missile: MissileConfig,
+    pub weapon: WeaponConfig,
+    pub asteroid: AsteroidConfig,
+    pub saucer: SaucerConfig,
+    pub powerup: PowerUpConfig,
+    pub wave: WaveConfig,
+    pub score: ScoreConfig,
+}
+
+/// Erreur de chargement d'un fichier de réglages.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// Le fichier n'a pas pu être lu.
+    Io(io::Error),
+    /// Le fichier n'est pas un TOML valide ou contient un champ inconnu.
+    Parse(toml::de::Error),
+    /// Une valeur est hors des limites autorisées.
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "lecture impossible : {}", e),
+            ConfigError::Parse(e) => write!(f, "fichier invalide : {}", e),
+            ConfigError::Invalid(message) => write!(f, "valeur invalide : {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl GameConfig {
+    /// Retourne les points gagnés en détruisant un astéroïde.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - La taille de l'astéroïde détruit.
+    ///
+    /// # Returns
+    ///
+    /// Les points de sa catégorie de taille, 0 si la taille n'en fait partie d'aucune.
+    pub fn points_for(&self, size: f32) -> u32 {
+        if size == self.asteroid.init_size {
+            self.score.large
+        } else if size == self.asteroid.medium_size {
+            self.score.medium
+        } else if size == self.asteroid.small_size {
+            self.score.small
+        } else {
+            0
+        }
+    }
+
+    /// Charge et valide les réglages depuis un fichier TOML.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Le chemin du fichier de réglages.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        Self::from_toml(&fs::read_to_string(path).map_err(ConfigError::Io)?)
+    }
+
+    /// Lit et valide des réglages au format TOML.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Le contenu du fichier de réglages.
+    pub fn from_toml(text: &str) -> Result<Self, ConfigError> {
+        let mut table: toml::Table = toml::from_str(text).map_err(ConfigError::Parse)?;
+        // Les commandes du joueur ne changent pas la partie : elles sont lues par `Controls`
+        table.remove("controls");
+        let config: Self = table.try_into().map_err(ConfigError::Parse)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Ecrit les réglages au format TOML.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("les réglages sont toujours sérialisables en TOML")
+    }
+
+    /// Vérifie que chaque valeur est dans ses limites.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` si les réglages sont utilisables, sinon une erreur nommant la première valeur fautive.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let ship = &self.ship;
+        positive("ship.max_speed", ship.max_speed)?;
+        positive("ship.min_speed", ship.min_speed)?;
+        positive("ship.acceleration", ship.acceleration)?;
+        positive("ship.rotation_speed", ship.rotation_speed)?;
+        positive("ship.friction", ship.friction)?;
+        check(ship.friction <= 1.0, || format!("ship.friction doit être entre 0 et 1 (lu {})", ship.friction))?;
+        check(ship.min_speed < ship.max_speed, || {
+            format!("ship.min_speed ({}) doit être inférieur à ship.max_speed ({})", ship.min_speed, ship.max_speed)
+        })?;
+        check(ship.initial_shield >= 1, || {
+            format!("ship.initial_shield doit être au moins 1 (lu {})", ship.initial_shield)
+        })?;
+        non_negative("ship.invulnerability_time", ship.invulnerability_time)?;
+        non_negative("ship.respawn_clear_radius", ship.respawn_clear_radius)?;
+        non_negative("ship.hyperspace_time", ship.hyperspace_time)?;
+        non_negative("ship.hyperspace_cooldown", ship.hyperspace_cooldown)?;
+        check((0.0..=1.0).contains(&ship.hyperspace_risk), || {
+            format!("ship.hyperspace_risk doit être entre 0 et 1 (lu {})", ship.hyperspace_risk)
+        })?;
+
+        positive("missile.speed", self.missile.speed)?;
+        positive("missile.lifetime", self.missile.lifetime)?;
+
+        let weapon = &self.weapon;
+        non_negative("weapon.cooldown", weapon.cooldown)?;
+        check(weapon.max_missiles >= 1, || "weapon.max_missiles doit être au moins 1".to_string())?;
+        non_negative("weapon.heat_per_shot", weapon.heat_per_shot)?;
+        positive("weapon.cooling_rate", weapon.cooling_rate)?;
+
+        let asteroid = &self.asteroid;
+        positive("asteroid.init_size", asteroid.init_size)?;
+        positive("asteroid.medium_size", asteroid.medium_size)?;
+        positive("asteroid.small_size", asteroid.small_size)?;
+        positive("asteroid.speed", asteroid.speed)?;
+        check(asteroid.init_size > asteroid.medium_size && asteroid.medium_size > asteroid.small_size, || {
+            format!(
+                "les tailles doivent décroître : asteroid.init_size ({}) > asteroid.medium_size ({}) > asteroid.small_size ({})",
+                asteroid.init_size, asteroid.medium_size, asteroid.small_size
+            )
+        })?;
+        let fragments = 1..=AsteroidConfig::MAX_FRAGMENTS;
+        check(fragments.contains(&asteroid.fragments_large) && fragments.contains(&asteroid.fragments_medium), || {
+            format!(
+                "asteroid.fragments_large et asteroid.fragments_medium doivent être entre 1 et {}",
+                AsteroidConfig::MAX_FRAGMENTS
+            )
+        })?;
+        non_negative("asteroid.split_speed", asteroid.split_speed)?;
+        non_negative("asteroid.chip_speed", asteroid.chip_speed)?;
+        check(asteroid.min_count >= 1, || "asteroid.min_count doit être au moins 1".to_string())?;
+        check(asteroid.max_count <= AsteroidConfig::MAX_COUNT, || {
+            format!("asteroid.max_count doit être au plus {} (lu {})", AsteroidConfig::MAX_COUNT, asteroid.max_count)
+        })?;
+        check(asteroid.min_count <= asteroid.max_count, || {
+            format!(
+                "asteroid.min_count ({}) doit être inférieur ou égal à asteroid.max_count ({})",
+                asteroid.min_count, asteroid.max_count
+            )
+        })?;
+
+        let saucer = &self.saucer;
+        positive("saucer.large_size", saucer.large_size)?;
+        positive("saucer.small_size", saucer.small_size)?;
+        positive("saucer.speed", saucer.speed)?;
+        positive("saucer.missile_speed", saucer.missile_speed)?;
+        positive("saucer.missile_lifetime", saucer.missile_lifetime)?;
+        positive("saucer.fire_interval", saucer.fire_interval)?;
+        positive("saucer.spawn_interval", saucer.spawn_interval)?;
+        check((0.0..=1.0).contains(&saucer.small_chance), || {
+            format!("saucer.small_chance doit être entre 0 et 1 (lu {})", saucer.small_chance)
+        })?;
+        non_negative("saucer.aim_error", saucer.aim_error)?;
+
+        let powerup = &self.powerup;
+        check((0.0..=1.0).contains(&powerup.drop_chance), || {
+            format!("powerup.drop_chance doit être entre 0 et 1 (lu {})", powerup.drop_chance)
+        })?;
+        positive("powerup.size", powerup.size)?;
+        non_negative("powerup.speed", powerup.speed)?;
+        positive("powerup.lifetime", powerup.lifetime)?;
+        positive("powerup.duration", powerup.duration)?;
+        check(powerup.max_shield >= 1, || {
+            format!("powerup.max_shield doit être au moins 1 (lu {})", powerup.max_shield)
+        })?;
+        non_negative("powerup.multi_shot_spread", powerup.multi_shot_spread)?;
+        positive("powerup.rapid_fire_interval", powerup.rapid_fire_interval)?;
+
+        let wave = &self.wave;
+        check(wave.speed_factor.is_finite() && wave.speed_factor >= 1.0, || {
+            format!("wave.speed_factor doit être au moins 1 (lu {})", wave.speed_factor)
+        })?;
+        check(wave.max_speed.is_finite() && wave.max_speed >= asteroid.speed, || {
+            format!("wave.max_speed ({}) doit être au moins asteroid.speed ({})", wave.max_speed, asteroid.speed)
+        })?;
+        check((asteroid.max_count..=AsteroidConfig::MAX_COUNT).contains(&wave.max_count), || {
+            format!(
+                "wave.max_count doit être entre asteroid.max_count ({}) et {} (lu {})",
+                asteroid.max_count, AsteroidConfig::MAX_COUNT, wave.max_count
+            )
+        })?;
+        non_negative("wave.transition_time", wave.transition_time)?;
+        Ok(())
+    }
+}
+
+/// Vérifie qu'une valeur est un nombre fini strictement positif.
+fn positive(name: &str, value: f32) -> Result<(), ConfigError> {
+    check(value.is_finite() && value > 0.0, || format!("{} doit être strictement positif (lu {})", name, value))
+}
+
+/// Vérifie qu'une valeur est un nombre fini positif ou nul.
+fn non_negative(name: &str, value: f32) -> Result<(), ConfigError> {
+    check(value.is_finite() && value >= 0.0, || format!("{} doit être positif ou nul (lu {})", name, value))
+}
+
+/// Retourne une erreur construite par `message` si la condition n'est pas remplie.
+fn check(condition: bool, message: impl FnOnce() -> String) -> Result<(), ConfigError> {
+    if condition {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid(message()))
+    }
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_match_constants() {
+        let config = GameConfig::from_toml("").unwrap();
+
+        assert_eq!(config, GameConfig::default());
+        assert_eq!(config.ship.max_speed, Spaceship::MAX_SPEED);
+        assert_eq!(config.missile.speed, Missile::SPEED);
+        assert_eq!(config.asteroid.init_size, Asteroid::ASTEROID_INIT_SIZE);
+    }
+
+    #[test]
+    fn test_shipped_file() {
+        let config = GameConfig::load(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/config.toml")).unwrap();
+
+        assert_eq!(config, GameConfig::default());
+    }
+
+    #[test]
+    fn test_partial_file() {
+        let config = GameConfig::from_toml("[ship]\nacceleration = 900.0\n\n[asteroid]\nmax_count = 12\n").unwrap();
+
+        assert_eq!(config.ship.acceleration, 900.0);
+        assert_eq!(config.ship.friction, Spaceship::FRICTION);
+        assert_eq!(config.asteroid.max_count, 12);
+    }
+
+    #[test]
+    fn test_toml_roundtrip() {
+        let config = GameConfig::default();
+
+        assert_eq!(GameConfig::from_toml(&config.to_toml()).unwrap(), config);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        let err = GameConfig::from_toml("[ship]\nfriction = 1.5\n").unwrap_err();
+        assert!(err.to_string().contains("ship.friction"));
+
+        let err = GameConfig::from_toml("[asteroid]\nmin_count = 9\nmax_count = 3\n").unwrap_err();
+        assert!(err.to_string().contains("asteroid.min_count"));
+
+        let err = GameConfig::from_toml("[asteroid]\nmax_count = 4000000000\n").unwrap_err();
+        assert!(err.to_string().contains("asteroid.max_count"));
+
+        let err = GameConfig::from_toml("[asteroid]\nfragments_large = 1000\n").unwrap_err();
+        assert!(err.to_string().contains("asteroid.fragments_large"));
+
+        let err = GameConfig::from_toml("[asteroid]\nmedium_size = 60.0\n").unwrap_err();
+        assert!(matches!(err, ConfigError::Invalid(_)));
+
+        let err = GameConfig::from_toml("[wave]\nspeed_factor = 0.5\n").unwrap_err();
+        assert!(err.to_string().contains("wave.speed_factor"));
+
+        let err = GameConfig::from_toml("[wave]\nmax_speed = 10.0\n").unwrap_err();
+        assert!(err.to_string().contains("wave.max_speed"));
+
+        let err = GameConfig::from_toml("[ship]\ninvulnerability_time = -1.0\n").unwrap_err();
+        assert!(err.to_string().contains("ship.invulnerability_time"));
+    }
+
+    #[test]
+    fn test_unknown_field() {
+        let err = GameConfig::from_toml("[ship]\nmax_sped = 10.0\n").unwrap_err();
+
+        assert!(matches!(err, ConfigError::Parse(_)));
+        assert!(err.to_string().contains("max_sped"));
+    }
+
+    #[test]
+    fn test_points_for() {
+        let config = GameConfig::default();
+
+        assert_eq!(config.points_for(Asteroid::ASTEROID_INIT_SIZE), 20);
+        assert_eq!(config.points_for(Asteroid::ASTEROID_MEDIUM_SIZE), 50);
+        assert_eq!(config.points_for(Asteroid::ASTEROID_SMALL_SIZE), 100);
+        assert_eq!(config.points_for(7.0), 0);
+    }
+
+    #[test]
+    fn test_split_size() {
+        let config = AsteroidConfig::default();
+
+        assert_eq!(config.split_size(config.init_size), Some(config.medium_size));
+        assert_eq!(config.split_size(config.medium_size), Some(config.small_size));
+        assert_eq!(config.split_size(config.small_size), None);
+    }
+}
@@ -0,0 +1,500 @@
+//! Commandes du joueur : chaque action du jeu est liée à des touches du clavier
+//! ou à des boutons de la souris.
+//!
+//! Les liaisons sont lues dans la section `[controls]` du fichier de réglages : un schéma
+//! de base (`arrows`, `wasd` ou `zqsd`), puis, pour chaque action, une liste facultative
+//! qui remplace celle du schéma. Cette section ne fait pas partie de [`GameConfig`](crate::GameConfig) :
+//! elle ne change pas la partie, et n'est donc ni sauvegardée ni rejouée.
+//!
+//! Les liaisons modifiées en jeu sont enregistrées dans un fichier propre au joueur,
+//! qui a la même section et se lit par-dessus celle du fichier de réglages
+//! (voir [`Controls::load_over`]) : le fichier de réglages livré n'est jamais réécrit.
+//!
+//! ```toml
+//! [controls]
+//! scheme = "zqsd"
+//! fire = ["Space", "MouseLeft"]
+//! ```
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use macroquad::prelude::{KeyCode, MouseButton};
+use serde::{Deserialize, Serialize};
+use crate::config::ConfigError;
+
+/// Les actions du joueur.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Thrust,
+    Brake,
+    RotateLeft,
+    RotateRight,
+    Fire,
+    Hyperspace,
+    Pause,
+    Quit,
+}
+
+impl Action {
+    /// Toutes les actions, dans l'ordre du menu des commandes.
+    pub const ALL: [Action; 8] = [
+        Action::Thrust,
+        Action::Brake,
+        Action::RotateLeft,
+        Action::RotateRight,
+        Action::Fire,
+        Action::Hyperspace,
+        Action::Pause,
+        Action::Quit,
+    ];
+
+    /// Retourne le nom de l'action à l'écran.
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Thrust => "Thrust",
+            Action::Brake => "Brake",
+            Action::RotateLeft => "Rotate left",
+            Action::RotateRight => "Rotate right",
+            Action::Fire => "Fire",
+            Action::Hyperspace => "Hyperspace",
+            Action::Pause => "Pause",
+            Action::Quit => "Quit",
+        }
+    }
+
+    /// Retourne la position de l'action dans [`Action::ALL`].
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|&action| action == self).unwrap()
+    }
+}
+
+/// Une touche du clavier ou un bouton de la souris.
+///
+/// Dans le fichier de réglages, une touche s'écrit avec le nom de sa constante
+/// macroquad (`"Up"`, `"Space"`, `"Z"`, `"Key1"`, `"LeftShift"`...) et un bouton
+/// de la souris `"MouseLeft"`, `"MouseRight"` ou `"MouseMiddle"`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+impl Binding {
+    /// Les touches qui peuvent être liées à une action.
+    pub const KEYS: [KeyCode; 82] = {
+        use KeyCode::*;
+        [
+            Up, Down, Left, Right, Space, Enter, Tab, Backspace, Escape, Insert, Delete, Home, End, PageUp,
+            PageDown, LeftShift, RightShift, LeftControl, RightControl, LeftAlt, RightAlt, A, B, C, D, E, F, G,
+            H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, Key0, Key1, Key2, Key3, Key4, Key5, Key6,
+            Key7, Key8, Key9, Kp0, Kp1, Kp2, Kp3, Kp4, Kp5, Kp6, Kp7, Kp8, Kp9, KpEnter, Comma, Period, Slash,
+            Semicolon, Apostrophe, Minus, Equal, LeftBracket, RightBracket, Backslash, GraveAccent, F2, F3,
+            F4,
+        ]
+    };
+    /// Les boutons de la souris qui peuvent être liés à une action, avec leur nom.
+    pub const MOUSE_BUTTONS: [(MouseButton, &'static str); 3] = [
+        (MouseButton::Left, "MouseLeft"),
+        (MouseButton::Right, "MouseRight"),
+        (MouseButton::Middle, "MouseMiddle"),
+    ];
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{:?}", key),
+            Binding::Mouse(button) => {
+                let name = Self::MOUSE_BUTTONS.iter().find(|(b, _)| b == button).map_or("MouseUnknown", |(_, name)| name);
+                f.write_str(name)
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for Binding {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Self::KEYS
+            .iter()
+            .map(|&key| Binding::Key(key))
+            .chain(Self::MOUSE_BUTTONS.iter().map(|&(button, _)| Binding::Mouse(button)))
+            .find(|binding| binding.to_string() == name)
+            .ok_or_else(|| format!("touche inconnue \"{}\"", name))
+    }
+}
+
+impl From<Binding> for String {
+    fn from(binding: Binding) -> Self {
+        binding.to_string()
+    }
+}
+
+/// Les schémas de touches de base.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scheme {
+    /// Flèches pour piloter, Espace pour tirer.
+    #[default]
+    Arrows,
+    /// W, A, S, D pour piloter (clavier QWERTY), Espace ou clic gauche pour tirer.
+    Wasd,
+    /// Z, Q, S, D pour piloter (clavier AZERTY), Espace ou clic gauche pour tirer.
+    Zqsd,
+}
+
+impl Scheme {
+    /// Tous les schémas, dans l'ordre où le menu les propose.
+    pub const ALL: [Scheme; 3] = [Scheme::Arrows, Scheme::Wasd, Scheme::Zqsd];
+
+    /// Retourne les touches de déplacement du schéma : poussée, frein, gauche, droite.
+    fn movement_keys(self) -> [KeyCode; 4] {
+        match self {
+            Scheme::Arrows => [KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right],
+            Scheme::Wasd => [KeyCode::W, KeyCode::S, KeyCode::A, KeyCode::D],
+            Scheme::Zqsd => [KeyCode::Z, KeyCode::S, KeyCode::Q, KeyCode::D],
+        }
+    }
+
+    /// Retourne le schéma suivant, pour le menu des commandes.
+    pub fn next(self) -> Scheme {
+        let index = Self::ALL.iter().position(|&scheme| scheme == self).unwrap();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// La section `[controls]` telle qu'elle est écrite dans le fichier de réglages.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ControlsSection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scheme: Option<Scheme>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thrust: Option<Vec<Binding>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    brake: Option<Vec<Binding>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rotate_left: Option<Vec<Binding>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rotate_right: Option<Vec<Binding>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fire: Option<Vec<Binding>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hyperspace: Option<Vec<Binding>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pause: Option<Vec<Binding>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quit: Option<Vec<Binding>>,
+}
+
+impl ControlsSection {
+    /// Retourne les listes de la section, dans l'ordre de [`Action::ALL`].
+    fn lists(&mut self) -> [&mut Option<Vec<Binding>>; 8] {
+        [
+            &mut self.thrust,
+            &mut self.brake,
+            &mut self.rotate_left,
+            &mut self.rotate_right,
+            &mut self.fire,
+            &mut self.hyperspace,
+            &mut self.pause,
+            &mut self.quit,
+        ]
+    }
+}
+
+/// Le fichier de réglages, dont seule la section `[controls]` est lue ici.
+#[derive(Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    controls: ControlsSection,
+}
+
+/// Les liaisons de chaque action.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Controls {
+    scheme: Scheme,
+    bindings: [Vec<Binding>; 8], // Dans l'ordre de Action::ALL
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        Self::from_scheme(Scheme::default())
+    }
+}
+
+impl Controls {
+    /// Crée les liaisons d'un schéma de touches.
+    ///
+    /// # Arguments
+    ///
+    /// * `scheme` - Le schéma de touches.
+    pub fn from_scheme(scheme: Scheme) -> Self {
+        let [thrust, brake, left, right] = scheme.movement_keys().map(|key| vec![Binding::Key(key)]);
+        let mut fire = vec![Binding::Key(KeyCode::Space)];
+        if scheme != Scheme::Arrows {
+            // La main droite reste sur la souris
+            fire.push(Binding::Mouse(MouseButton::Left));
+        }
+        Self {
+            scheme,
+            bindings: [
+                thrust,
+                brake,
+                left,
+                right,
+                fire,
+                vec![Binding::Key(KeyCode::H)],
+                vec![Binding::Key(KeyCode::P)],
+                vec![Binding::Key(KeyCode::Escape)],
+            ],
+        }
+    }
+
+    /// Retourne le schéma de touches de base.
+    pub fn scheme(&self) -> Scheme {
+        self.scheme
+    }
+
+    /// Retourne les touches et boutons liés à une action.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - L'action.
+    pub fn bindings(&self, action: Action) -> &[Binding] {
+        &self.bindings[action.index()]
+    }
+
+    /// Retourne l'action liée à une touche ou un bouton, s'il y en a une.
+    ///
+    /// # Arguments
+    ///
+    /// * `binding` - La touche ou le bouton.
+    pub fn action_for(&self, binding: Binding) -> Option<Action> {
+        Action::ALL.into_iter().find(|&action| self.bindings(action).contains(&binding))
+    }
+
+    /// Lie une touche ou un bouton à une action. Il est retiré de l'action qui l'utilisait,
+    /// pour qu'une touche ne déclenche jamais deux actions.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - L'action.
+    /// * `binding` - La touche ou le bouton.
+    /// * `replace` - `true` pour remplacer les liaisons de l'action, `false` pour en ajouter une.
+    pub fn bind(&mut self, action: Action, binding: Binding, replace: bool) {
+        for bindings in self.bindings.iter_mut() {
+            bindings.retain(|&b| b != binding);
+        }
+        let bindings = &mut self.bindings[action.index()];
+        if replace {
+            bindings.clear();
+        }
+        bindings.push(binding);
+    }
+
+    /// Rend à une action les liaisons du schéma de base.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - L'action.
+    pub fn reset(&mut self, action: Action) {
+        self.bindings[action.index()].clear();
+        for &binding in Self::from_scheme(self.scheme).bindings(action) {
+            self.bind(action, binding, false);
+        }
+    }
+
+    /// Lit les liaisons dans le fichier de réglages. Sans fichier, les liaisons
+    /// du schéma par défaut sont utilisées.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Le chemin du fichier de réglages.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        Self::default().load_over(path)
+    }
+
+    /// Lit des liaisons par-dessus celles-ci : un schéma donné par le fichier repart
+    /// de ce schéma, et chaque liste donnée remplace celle de l'action. Sans fichier,
+    /// les liaisons restent inchangées.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Le chemin du fichier à lire, par exemple les commandes du joueur.
+    pub fn load_over(&self, path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        match fs::read_to_string(path) {
+            Ok(text) => self.clone().with_section(Self::parse_section(&text)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(self.clone()),
+            Err(e) => Err(ConfigError::Io(e)),
+        }
+    }
+
+    /// Lit les liaisons dans la section `[controls]` d'un fichier de réglages TOML.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Le contenu du fichier de réglages.
+    pub fn from_toml(text: &str) -> Result<Self, ConfigError> {
+        Self::default().with_section(Self::parse_section(text)?)
+    }
+
+    /// Lit la section `[controls]` d'un fichier TOML, vide si elle est absente.
+    fn parse_section(text: &str) -> Result<ControlsSection, ConfigError> {
+        Ok(toml::from_str::<ConfigFile>(text).map_err(ConfigError::Parse)?.controls)
+    }
+
+    /// Applique une section `[controls]` à ces liaisons, puis vérifie le résultat.
+    fn with_section(mut self, mut section: ControlsSection) -> Result<Self, ConfigError> {
+        if let Some(scheme) = section.scheme {
+            self = Self::from_scheme(scheme);
+        }
+        for (action, list) in Action::ALL.into_iter().zip(section.lists()) {
+            if let Some(list) = list.take() {
+                self.bindings[action.index()] = list;
+            }
+        }
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Vérifie que chaque action a au moins une liaison, et qu'aucune touche
+    /// ne déclenche deux actions.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for action in Action::ALL {
+            let bindings = self.bindings(action);
+            if bindings.is_empty() {
+                return Err(ConfigError::Invalid(format!("controls : aucune touche pour \"{}\"", action.label())));
+            }
+            for &binding in bindings {
+                let owner = self.action_for(binding).unwrap();
+                if owner != action {
+                    return Err(ConfigError::Invalid(format!(
+                        "controls : {} est lié à \"{}\" et à \"{}\"",
+                        binding,
+                        owner.label(),
+                        action.label()
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Ecrit la section `[controls]` au format TOML : le schéma, puis la liste de chaque action.
+    pub fn to_toml(&self) -> String {
+        let mut section = ControlsSection { scheme: Some(self.scheme), ..Default::default() };
+        for (action, list) in Action::ALL.into_iter().zip(section.lists()) {
+            *list = Some(self.bindings(action).to_vec());
+        }
+        toml::to_string(&section).expect("les commandes sont toujours sérialisables en TOML")
+    }
+
+    /// Enregistre les liaisons dans la section `[controls]` du fichier de réglages,
+    /// en laissant intactes les autres sections et leurs commentaires.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Le chemin du fichier de réglages, créé s'il n'existe pas.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        let invalid = |e: toml_edit::TomlError| io::Error::new(io::ErrorKind::InvalidData, e);
+        let mut document = text.parse::<toml_edit::DocumentMut>().map_err(invalid)?;
+        let section = self.to_toml().parse::<toml_edit::DocumentMut>().map_err(invalid)?;
+        document["controls"] = toml_edit::Item::Table(section.as_table().clone());
+        fs::write(path, document.to_string())
+    }
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scheme_and_overrides() {
+        let controls = Controls::from_toml("[controls]\nscheme = \"zqsd\"\nhyperspace = [\"E\", \"MouseRight\"]\n").unwrap();
+
+        assert_eq!(controls.bindings(Action::Thrust), [Binding::Key(KeyCode::Z)]);
+        assert_eq!(controls.bindings(Action::RotateLeft), [Binding::Key(KeyCode::Q)]);
+        assert_eq!(controls.bindings(Action::Hyperspace), [Binding::Key(KeyCode::E), Binding::Mouse(MouseButton::Right)]);
+        assert_eq!(controls.action_for(Binding::Mouse(MouseButton::Left)), Some(Action::Fire));
+        // Les autres sections du fichier ne sont pas lues ici
+        assert_eq!(Controls::from_toml("[ship]\nacceleration = 1.0\n").unwrap(), Controls::default());
+    }
+
+    #[test]
+    fn test_shipped_file() {
+        let controls = Controls::load(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/config.toml")).unwrap();
+
+        assert_eq!(controls, Controls::default());
+    }
+
+    #[test]
+    fn test_player_file_over_config() {
+        let path = std::env::temp_dir().join(format!("asteroid-player-controls-{}.toml", std::process::id()));
+        let config = Controls::from_scheme(Scheme::Zqsd);
+        assert_eq!(config.load_over(&path).unwrap(), config);
+
+        fs::write(&path, "[controls]\npause = [\"Tab\"]\n").unwrap();
+        let controls = config.load_over(&path).unwrap();
+        fs::write(&path, "[controls]\nscheme = \"wasd\"\n").unwrap();
+        let other_scheme = config.load_over(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // Seule la liste donnée change, le reste vient du fichier de réglages
+        assert_eq!(controls.bindings(Action::Pause), [Binding::Key(KeyCode::Tab)]);
+        assert_eq!(controls.bindings(Action::Thrust), [Binding::Key(KeyCode::Z)]);
+        assert_eq!(other_scheme, Controls::from_scheme(Scheme::Wasd));
+    }
+
+    #[test]
+    fn test_invalid_bindings() {
+        assert!(matches!(Controls::from_toml("[controls]\nfire = [\"Nope\"]\n"), Err(ConfigError::Parse(_))));
+        assert!(matches!(Controls::from_toml("[controls]\npause = []\n"), Err(ConfigError::Invalid(_))));
+        // Espace est déjà la touche de tir
+        assert!(matches!(Controls::from_toml("[controls]\nthrust = [\"Space\"]\n"), Err(ConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_bind_moves_binding() {
+        let mut controls = Controls::default();
+
+        controls.bind(Action::Thrust, Binding::Key(KeyCode::Space), true);
+        assert_eq!(controls.bindings(Action::Thrust), [Binding::Key(KeyCode::Space)]);
+        assert!(controls.bindings(Action::Fire).is_empty());
+        // Le tir n'a plus de touche : le menu refuse d'enregistrer
+        assert!(matches!(controls.validate(), Err(ConfigError::Invalid(_))));
+        controls.reset(Action::Fire);
+        assert_eq!(controls.bindings(Action::Fire), [Binding::Key(KeyCode::Space)]);
+        assert!(controls.bindings(Action::Thrust).is_empty());
+        assert!(controls.validate().is_err());
+        controls.reset(Action::Thrust);
+        assert!(controls.validate().is_ok());
+    }
+
+    #[test]
+    fn test_save_keeps_other_sections() {
+        let path = std::env::temp_dir().join(format!("asteroid-controls-{}.toml", std::process::id()));
+        fs::write(&path, "# Réglages\n[ship]\nacceleration = 500.0 # plus vif\n").unwrap();
+        let mut controls = Controls::from_scheme(Scheme::Wasd);
+        controls.bind(Action::Pause, Binding::Key(KeyCode::Tab), true);
+
+        controls.save(&path).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(text.contains("acceleration = 500.0 # plus vif"));
+        assert_eq!(Controls::from_toml(&text).unwrap(), controls);
+        assert_eq!(crate::GameConfig::from_toml(&text).unwrap().ship.acceleration, 500.0);
+    }
+}
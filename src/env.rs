@@ -0,0 +1,332 @@
+//! Environnement d'apprentissage par renforcement au-dessus de la simulation.
+//!
+//! L'API suit celle de Gym : [`Environment::reset`] commence une partie à partir d'une
+//! graine et renvoie la première [`Observation`], puis [`Environment::step`] applique une
+//! commande et renvoie l'observation suivante, la récompense et la fin de l'épisode.
+//! Rien n'est dessiné : l'environnement tourne sans fenêtre, à des milliers de pas par
+//! seconde (voir `cargo bench --bench env`).
+//!
+//! Les commandes sont des [`PlayerInput`] : un agent à actions discrètes peut en choisir
+//! une parmi les 64 données par [`PlayerInput::from_bits`].
+use macroquad::prelude::*;
+use crate::config::{ConfigError, GameConfig};
+use crate::stellarobject::StellarObject;
+use crate::torus;
+use crate::world::{GameEvent, GameWorld, PlayerInput};
+
+/// Poids des récompenses données à l'agent.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RewardConfig {
+    /// Récompense par point marqué.
+    pub score: f32,
+    /// Récompense à chaque pas où le vaisseau est encore en jeu.
+    pub survival: f32,
+    /// Récompense (négative) quand le vaisseau est touché.
+    pub ship_hit: f32,
+    /// Récompense (négative) à la fin de la partie.
+    pub game_over: f32,
+    /// Récompense (négative) par missile tiré, pour décourager le tir continu.
+    pub missile_fired: f32,
+}
+
+impl Default for RewardConfig {
+    fn default() -> Self {
+        Self { score: 0.01, survival: 0.001, ship_hit: -1.0, game_over: -5.0, missile_fired: 0.0 }
+    }
+}
+
+/// Réglages de l'environnement.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EnvConfig {
+    /// Réglages des parties jouées.
+    pub game: GameConfig,
+    /// Nombre d'astéroïdes les plus proches décrits dans une observation.
+    pub nearest_asteroids: usize,
+    /// Nombre de missiles les plus proches décrits dans une observation.
+    pub nearest_missiles: usize,
+    /// Nombre de pas de simulation joués avec la même commande à chaque appel de `step`.
+    pub frame_skip: u32,
+    /// Nombre maximal de pas de simulation d'un épisode, sans limite si `None`.
+    pub max_ticks: Option<u64>,
+    /// Poids des récompenses.
+    pub reward: RewardConfig,
+}
+
+impl Default for EnvConfig {
+    fn default() -> Self {
+        Self {
+            game: GameConfig::default(),
+            nearest_asteroids: 8,
+            nearest_missiles: 4,
+            frame_skip: 1,
+            max_ticks: Some(60 * 60 * 5),
+            reward: RewardConfig::default(),
+        }
+    }
+}
+
+/// Un objet vu depuis le vaisseau.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ObjectObservation {
+    /// Position par rapport au vaisseau, par le plus court chemin à travers les bords.
+    pub offset: Vec2,
+    /// Vitesse par rapport au vaisseau, en unités par seconde.
+    pub velocity: Vec2,
+    /// Taille de l'objet (rayon d'un astéroïde, 0 pour un missile).
+    pub size: f32,
+    /// Vrai pour un missile tiré par une soucoupe.
+    pub hostile: bool,
+}
+
+impl ObjectObservation {
+    /// Nombre de valeurs d'un objet dans [`Observation::to_vec`].
+    pub const LEN: usize = 6;
+}
+
+/// Ce que l'agent voit de la partie.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Observation {
+    /// Position du vaisseau.
+    pub position: Vec2,
+    /// Vitesse du vaisseau, en unités par seconde.
+    pub speed: Vec2,
+    /// Orientation du vaisseau en radians (0 vers le haut, sens horaire).
+    pub orientation: f32,
+    /// Niveau du bouclier.
+    pub shield: i32,
+    /// Les astéroïdes les plus proches, du plus proche au plus lointain.
+    pub asteroids: Vec<ObjectObservation>,
+    /// Les missiles les plus proches, du vaisseau et des soucoupes, du plus proche au plus lointain.
+    pub missiles: Vec<ObjectObservation>,
+}
+
+impl Observation {
+    /// Nombre de valeurs décrivant le vaisseau dans [`Observation::to_vec`].
+    pub const SHIP_LEN: usize = 7;
+
+    /// Met l'observation à plat pour un réseau de neurones, sans mise à l'échelle :
+    /// position (x, y), vitesse (x, y), sinus et cosinus de l'orientation, bouclier,
+    /// puis pour chaque objet position relative (x, y), vitesse relative (x, y), taille
+    /// et 1 s'il est hostile.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Les réglages de l'environnement : les listes plus courtes que
+    ///   `nearest_asteroids` et `nearest_missiles` sont complétées par des zéros.
+    ///
+    /// # Returns
+    ///
+    /// Un vecteur de longueur [`Environment::observation_len`], la même à chaque pas.
+    pub fn to_vec(&self, config: &EnvConfig) -> Vec<f32> {
+        let mut values = vec![
+            self.position.x,
+            self.position.y,
+            self.speed.x,
+            self.speed.y,
+            self.orientation.sin(),
+            self.orientation.cos(),
+            self.shield as f32,
+        ];
+        for (objects, count) in [(&self.asteroids, config.nearest_asteroids), (&self.missiles, config.nearest_missiles)] {
+            for i in 0..count {
+                let o = objects.get(i).copied().unwrap_or_default();
+                values.extend([o.offset.x, o.offset.y, o.velocity.x, o.velocity.y, o.size, o.hostile as u8 as f32]);
+            }
+        }
+        values
+    }
+}
+
+/// Environnement d'apprentissage : une partie sans fenêtre pilotée pas à pas.
+pub struct Environment {
+    config: EnvConfig,
+    world: GameWorld,
+}
+
+impl Environment {
+    /// Crée l'environnement, avec une première partie de graine 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Les réglages de l'environnement.
+    ///
+    /// # Returns
+    ///
+    /// L'environnement, ou une erreur si les réglages des parties sont incohérents,
+    /// comme pour un fichier de réglages (voir [`GameConfig::validate`]).
+    pub fn new(config: EnvConfig) -> Result<Self, ConfigError> {
+        config.game.validate()?;
+        let world = GameWorld::with_config(0, config.game);
+        Ok(Self { config, world })
+    }
+
+    /// Retourne les réglages de l'environnement.
+    pub fn config(&self) -> &EnvConfig {
+        &self.config
+    }
+
+    /// Retourne la partie en cours, pour l'afficher ou l'inspecter.
+    pub fn world(&self) -> &GameWorld {
+        &self.world
+    }
+
+    /// Retourne la longueur des vecteurs donnés par [`Observation::to_vec`].
+    pub fn observation_len(&self) -> usize {
+        Observation::SHIP_LEN + ObjectObservation::LEN * (self.config.nearest_asteroids + self.config.nearest_missiles)
+    }
+
+    /// Commence un nouvel épisode.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - La graine de la partie : la même graine donne la même partie.
+    ///
+    /// # Returns
+    ///
+    /// La première observation de l'épisode.
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.world = GameWorld::with_config(seed, self.config.game);
+        self.observe()
+    }
+
+    /// Applique une commande pendant `frame_skip` pas de simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - La commande de l'agent.
+    ///
+    /// # Returns
+    ///
+    /// L'observation suivante, la récompense cumulée sur ces pas, et vrai si l'épisode
+    /// est terminé (partie perdue ou `max_ticks` atteint). Après la fin d'un épisode,
+    /// il faut appeler [`Environment::reset`].
+    pub fn step(&mut self, input: &PlayerInput) -> (Observation, f32, bool) {
+        let weights = self.config.reward;
+        let mut reward = 0.0;
+        for _ in 0..self.config.frame_skip.max(1) {
+            if self.is_done() {
+                break;
+            }
+            let score = self.world.score();
+            for event in self.world.step(input) {
+                reward += match event {
+                    GameEvent::ShipHit => weights.ship_hit,
+                    GameEvent::GameOver => weights.game_over,
+                    GameEvent::MissileFired => weights.missile_fired,
+                    _ => 0.0,
+                };
+            }
+            reward += (self.world.score() - score) as f32 * weights.score;
+            if !self.world.is_game_over() {
+                reward += weights.survival;
+            }
+        }
+        (self.observe(), reward, self.is_done())
+    }
+
+    /// Indique si l'épisode est terminé.
+    fn is_done(&self) -> bool {
+        self.world.is_game_over() || self.config.max_ticks.is_some_and(|max| self.world.ticks() >= max)
+    }
+
+    /// Décrit la partie telle que la voit le vaisseau.
+    fn observe(&self) -> Observation {
+        let ship = self.world.spaceship();
+        let position = ship.get_position();
+        let screen_size = self.world.screen_size();
+        let relative = |object: &dyn StellarObject, hostile: bool| {
+            // La vitesse est déduite du dernier déplacement, valable pour tous les objets
+            let moved = torus::displacement(object.get_previous_position(), object.get_position(), screen_size);
+            ObjectObservation {
+                offset: torus::displacement(position, object.get_position(), screen_size),
+                velocity: moved / GameWorld::TICK - ship.get_speed(),
+                size: object.get_size(),
+                hostile,
+            }
+        };
+        let nearest = |mut objects: Vec<ObjectObservation>, count: usize| {
+            objects.sort_by(|a, b| a.offset.length_squared().total_cmp(&b.offset.length_squared()));
+            objects.truncate(count);
+            objects
+        };
+
+        let asteroids = self.world.asteroids().iter().map(|a| relative(a, false)).collect();
+        let missiles = self.world.missiles().iter().map(|m| relative(m, false))
+            .chain(self.world.enemy_missiles().iter().map(|m| relative(m, true)))
+            .collect();
+        Observation {
+            position,
+            speed: ship.get_speed(),
+            orientation: ship.get_orientation(),
+            shield: ship.get_shield(),
+            asteroids: nearest(asteroids, self.config.nearest_asteroids),
+            missiles: nearest(missiles, self.config.nearest_missiles),
+        }
+    }
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reset_is_reproducible() {
+        let mut env = Environment::new(EnvConfig::default()).unwrap();
+        let fire = PlayerInput { fire: true, rotate_left: true, ..Default::default() };
+
+        let first = env.reset(5);
+        let after: Vec<_> = (0..30).map(|_| env.step(&fire)).collect();
+        assert_eq!(env.reset(5), first);
+        let again: Vec<_> = (0..30).map(|_| env.step(&fire)).collect();
+
+        assert_eq!(after, again);
+        assert_ne!(env.reset(6), first);
+    }
+
+    #[test]
+    fn test_observation_has_fixed_length() {
+        let config = EnvConfig { nearest_asteroids: 20, nearest_missiles: 2, ..Default::default() };
+        let mut env = Environment::new(config).unwrap();
+
+        let observation = env.reset(0);
+
+        // Moins d'astéroïdes que demandé : la liste est complétée par des zéros
+        assert!(observation.asteroids.len() < 20);
+        assert_eq!(observation.to_vec(env.config()).len(), env.observation_len());
+        let distances: Vec<_> = observation.asteroids.iter().map(|a| a.offset.length()).collect();
+        assert!(distances.windows(2).all(|d| d[0] <= d[1]));
+        assert_eq!(observation.shield, env.world().spaceship().get_shield());
+    }
+
+    #[test]
+    fn test_invalid_game_config() {
+        let mut config = EnvConfig::default();
+        config.game.asteroid.min_count = config.game.asteroid.max_count + 1;
+
+        assert!(matches!(Environment::new(config), Err(ConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_reward_shaping_and_episode_end() {
+        let config = EnvConfig {
+            frame_skip: 4,
+            max_ticks: Some(40),
+            reward: RewardConfig { survival: 1.0, score: 0.0, ..Default::default() },
+            ..Default::default()
+        };
+        let mut env = Environment::new(config).unwrap();
+        env.reset(1);
+
+        let steps: Vec<_> = std::iter::from_fn(|| {
+            let (_, reward, done) = env.step(&PlayerInput::default());
+            Some((reward, done))
+        }).take(10).collect();
+
+        // 4 pas par appel, un point de survie par pas, et fin au 40e pas
+        assert!(steps.iter().all(|&(reward, _)| reward == 4.0));
+        assert!(steps[..9].iter().all(|&(_, done)| !done));
+        assert!(steps[9].1);
+        assert_eq!(env.world().ticks(), 40);
+    }
+}
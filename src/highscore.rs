@@ -0,0 +1,178 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+/// Une ligne du tableau des meilleurs scores.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScore {
+    /// Initiales du joueur, style borne d'arcade.
+    pub initials: String,
+    pub score: u32,
+    /// Vague atteinte.
+    pub wave: u32,
+}
+
+/// Tableau des meilleurs scores, trié du meilleur au moins bon.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScores {
+    entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    /// Nombre de lignes conservées dans le tableau.
+    pub const MAX_ENTRIES: usize = 10;
+    /// Nombre maximal de caractères des initiales.
+    pub const INITIALS_LEN: usize = 3;
+
+    /// Retourne les lignes du tableau, de la meilleure à la moins bonne.
+    pub fn entries(&self) -> &[HighScore] {
+        &self.entries
+    }
+
+    /// Indique si un score entre dans le tableau.
+    ///
+    /// # Arguments
+    ///
+    /// * `score` - Le score à tester.
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries.len() < Self::MAX_ENTRIES
+                || self.entries.last().is_some_and(|last| score > last.score))
+    }
+
+    /// Ajoute un score au tableau s'il y entre.
+    ///
+    /// A score égal, le plus ancien reste devant.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - Le score à ajouter.
+    ///
+    /// # Returns
+    ///
+    /// Le rang obtenu (0 pour le meilleur), ou `None` si le score n'entre pas dans le tableau.
+    pub fn insert(&mut self, entry: HighScore) -> Option<usize> {
+        if !self.qualifies(entry.score) {
+            return None;
+        }
+        let rank = self.entries.iter().position(|e| entry.score > e.score).unwrap_or(self.entries.len());
+        self.entries.insert(rank, entry);
+        self.entries.truncate(Self::MAX_ENTRIES);
+        Some(rank)
+    }
+
+    /// Lit le tableau depuis un fichier JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Le chemin du fichier.
+    ///
+    /// # Returns
+    ///
+    /// Le tableau lu, un tableau vide si le fichier n'existe pas encore, ou une erreur.
+    /// Un fichier illisible est renommé en `.bak` avant de renvoyer l'erreur, pour que
+    /// le prochain [`HighScores::save`] n'efface pas les anciens scores.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+        serde_json::from_str(&text).map_err(|e| {
+            let backup = path.with_extension("json.bak");
+            match fs::rename(path, &backup) {
+                Ok(()) => io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{} (fichier mis de côté dans {})", e, backup.display()),
+                ),
+                Err(_) => e.into(),
+            }
+        })
+    }
+
+    /// Ecrit le tableau dans un fichier JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Le chemin du fichier.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(initials: &str, score: u32) -> HighScore {
+        HighScore { initials: initials.to_string(), score, wave: 1 }
+    }
+
+    #[test]
+    fn test_insert_sorted_and_capped() {
+        let mut scores = HighScores::default();
+        for score in 1..=12 {
+            scores.insert(entry("AAA", score * 10));
+        }
+
+        assert_eq!(scores.entries().len(), HighScores::MAX_ENTRIES);
+        assert_eq!(scores.entries()[0].score, 120);
+        assert_eq!(scores.entries().last().unwrap().score, 30);
+        assert!(!scores.qualifies(30));
+        assert!(scores.qualifies(31));
+        assert_eq!(scores.insert(entry("BBB", 20)), None);
+        assert_eq!(scores.insert(entry("CCC", 115)), Some(1));
+    }
+
+    #[test]
+    fn test_ties_keep_oldest_first() {
+        let mut scores = HighScores::default();
+        scores.insert(entry("OLD", 50));
+
+        assert_eq!(scores.insert(entry("NEW", 50)), Some(1));
+        assert_eq!(scores.entries()[0].initials, "OLD");
+    }
+
+    #[test]
+    fn test_zero_does_not_qualify() {
+        assert!(!HighScores::default().qualifies(0));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join("asteroid_test_highscores.json");
+        let mut scores = HighScores::default();
+        scores.insert(entry("MAS", 1250));
+        scores.insert(entry("LYN", 900));
+
+        scores.save(&path).unwrap();
+        let loaded = HighScores::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, scores);
+    }
+
+    #[test]
+    fn test_corrupt_file_is_backed_up() {
+        let path = std::env::temp_dir().join("asteroid_test_highscores_corrupt.json");
+        let backup = path.with_extension("json.bak");
+        fs::write(&path, "{ pas du json").unwrap();
+
+        let err = HighScores::load(&path).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "{ pas du json");
+        fs::remove_file(&backup).unwrap();
+    }
+
+    #[test]
+    fn test_missing_file_is_empty() {
+        let path = std::env::temp_dir().join("asteroid_test_highscores_missing.json");
+
+        assert_eq!(HighScores::load(path).unwrap(), HighScores::default());
+    }
+}
@@ -0,0 +1,125 @@
+//! Sources des commandes du joueur.
+//!
+//! La boucle de jeu ne sait pas d'où viennent les commandes : à chaque pas de simulation,
+//! elle les demande à une [`InputSource`], qu'il s'agisse du clavier, d'un replay, d'un
+//! script de test ou d'un bot, puis les passe à [`GameWorld::step`].
+use std::collections::VecDeque;
+use crate::replay::Replay;
+use crate::world::{GameEvent, GameWorld, PlayerInput};
+
+/// Une source de commandes pour la partie.
+pub trait InputSource {
+    /// Retourne les commandes du prochain pas de simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - La partie telle qu'elle est avant ce pas, pour une source qui
+    ///   décide en la regardant (un bot par exemple).
+    ///
+    /// # Returns
+    ///
+    /// Les commandes du pas, ou `None` quand la source est épuisée (fin d'un replay
+    /// ou d'un script).
+    fn next_input(&mut self, world: &GameWorld) -> Option<PlayerInput>;
+}
+
+/// Un replay fournit la commande enregistrée pour le pas `world.ticks()` : il se
+/// rejoue sur la partie créée par [`Replay::new_world`].
+impl InputSource for Replay {
+    fn next_input(&mut self, world: &GameWorld) -> Option<PlayerInput> {
+        self.inputs().get(world.ticks() as usize).copied()
+    }
+}
+
+/// Une suite de commandes écrite à l'avance, chacune maintenue pendant un nombre
+/// de pas donné. Sert surtout aux tests.
+#[derive(Clone, Debug, Default)]
+pub struct ScriptedInput {
+    steps: VecDeque<(u64, PlayerInput)>, // Commandes restantes et nombre de pas à les maintenir
+}
+
+impl ScriptedInput {
+    /// Crée un script.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps` - Les commandes dans l'ordre, chacune avec le nombre de pas pendant
+    ///   lesquels la maintenir.
+    pub fn new(steps: impl IntoIterator<Item = (u64, PlayerInput)>) -> Self {
+        Self { steps: steps.into_iter().filter(|&(ticks, _)| ticks > 0).collect() }
+    }
+}
+
+impl InputSource for ScriptedInput {
+    fn next_input(&mut self, _world: &GameWorld) -> Option<PlayerInput> {
+        let (ticks, input) = self.steps.front_mut()?;
+        let input = *input;
+        *ticks -= 1;
+        if *ticks == 0 {
+            self.steps.pop_front();
+        }
+        Some(input)
+    }
+}
+
+/// Fait jouer une source sur une partie, sans fenêtre.
+///
+/// # Arguments
+///
+/// * `world` - La partie.
+/// * `source` - La source des commandes.
+/// * `max_ticks` - Le nombre maximal de pas à jouer.
+///
+/// # Returns
+///
+/// Les événements survenus. La partie s'arrête avant `max_ticks` si la source est
+/// épuisée ou si la partie est terminée.
+pub fn run(world: &mut GameWorld, source: &mut dyn InputSource, max_ticks: u64) -> Vec<GameEvent> {
+    let mut events = Vec::new();
+    for _ in 0..max_ticks {
+        if world.is_game_over() {
+            break;
+        }
+        let Some(input) = source.next_input(world) else { break };
+        events.extend(world.step(&input));
+    }
+    events
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stellarobject::StellarObject;
+
+    #[test]
+    fn test_script_holds_each_input() {
+        let world = GameWorld::new(0);
+        let fire = PlayerInput { fire: true, ..Default::default() };
+        let mut script = ScriptedInput::new([(2, fire), (0, PlayerInput::default()), (1, PlayerInput::default())]);
+
+        let inputs: Vec<_> = std::iter::from_fn(|| script.next_input(&world)).collect();
+
+        assert_eq!(inputs, [fire, fire, PlayerInput::default()]);
+    }
+
+    #[test]
+    fn test_replay_as_source() {
+        let thrust = PlayerInput { thrust: true, rotate_left: true, ..Default::default() };
+        let mut world = GameWorld::new(7);
+        let mut replay = Replay::new(world.seed(), *world.config());
+        let mut script = ScriptedInput::new([(30, thrust), (30, PlayerInput { fire: true, ..Default::default() })]);
+        while let Some(input) = script.next_input(&world) {
+            replay.record(input);
+            world.step(&input);
+        }
+
+        let mut replayed = replay.new_world();
+        run(&mut replayed, &mut replay, u64::MAX);
+
+        // La source s'arrête à la fin de l'enregistrement, sur la même partie
+        assert_eq!(replayed.ticks(), 60);
+        assert_eq!(replayed.spaceship().get_position(), world.spaceship().get_position());
+        assert_eq!(replayed.missiles().len(), world.missiles().len());
+    }
+}
@@ -0,0 +1,42 @@
+//! Simulation du jeu Asteroid, indépendante de toute fenêtre.
+//!
+//! Le binaire `asteroid` n'est qu'une interface macroquad au-dessus de [`GameWorld`] :
+//! bots, tests et outils peuvent piloter la simulation sans ouvrir de fenêtre.
+pub mod asteroid;
+pub mod autopilot;
+pub mod config;
+pub mod controls;
+pub mod env;
+pub mod highscore;
+pub mod input;
+pub mod missile;
+pub mod particles;
+pub mod polygon;
+pub mod powerup;
+pub mod replay;
+pub mod saucer;
+pub mod save;
+pub mod spaceship;
+pub mod spatialhash;
+pub mod stellarobject;
+pub mod timestep;
+pub mod torus;
+pub mod viewport;
+pub mod weapon;
+pub mod world;
+
+pub use asteroid::Asteroid;
+pub use autopilot::{Autopilot, Skill};
+pub use config::GameConfig;
+pub use highscore::{HighScore, HighScores};
+pub use input::InputSource;
+pub use missile::Missile;
+pub use powerup::{ActiveEffects, PowerUp, PowerUpKind};
+pub use replay::Replay;
+pub use saucer::{Saucer, SaucerKind};
+pub use spaceship::Spaceship;
+pub use spatialhash::SpatialHash;
+pub use stellarobject::StellarObject;
+pub use timestep::FixedTimestep;
+pub use weapon::Weapon;
+pub use world::{GameEvent, GameWorld, PlayerInput};
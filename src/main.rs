@@ -1,33 +1,41 @@
 /// Import des modules nécessaires.
-use asteroid::Asteroid;
-use miniquad::window::screen_size;
-use spaceship::Spaceship;
-use missile::Missile;
-use crate::stellarobject::StellarObject;
+use asteroid::{ActiveEffects, Asteroid, Autopilot, FixedTimestep, GameConfig, GameEvent, GameWorld, HighScore, HighScores, InputSource, Missile, PlayerInput, PowerUp, PowerUpKind, Replay, Saucer, Skill, Spaceship, StellarObject, Weapon};
+use asteroid::{polygon, torus};
+use asteroid::controls::{Action, Binding, Controls};
+use asteroid::particles::ParticleSystem;
+use asteroid::save::{load_game, save_game};
+use asteroid::viewport::Letterbox;
 use macroquad::prelude::*; 
 use macroquad::audio::{play_sound, load_sound, Sound, PlaySoundParams};
-use ::rand::{thread_rng, Rng};
-use std::time::{Instant,Duration};
-mod asteroid; 
-mod spaceship;
-mod missile ; 
-mod stellarobject;
+use std::path::PathBuf;
+use std::time::Duration;
 
 /// Dessin à l'écran .
 /// # Arguments
 ///
-/// * `asteroids` - Une référence à un vecteur des astreroids à dessiner.
-/// * `spaceship` - Une référence au visseau à dessiner.
-/// * `missiles` - Une référence à un vecteur de missiles à dessiner.
+/// * `world` - Une référence à l'état de la partie à dessiner.
+/// * `particles` - Les particules (explosions, flamme du réacteur).
+/// * `alpha` - La fraction du pas de simulation suivant déjà écoulée, pour interpoler les positions.
 /// * `background_texture` - Une référence à Texture2D qui represent l'image de fond d'écran.
-/// * `asteroid_texture` - Une référence à Texture2D qui represent l'image des asteroid.
 ///  
-async fn draw(asteroids: &Vec<Asteroid>,spaceship:&Spaceship,missiles:&Vec<Missile>,background_texture:&Texture2D,asteroid_texture:&Texture2D) {
-    draw_background(background_texture); // Dessine l'arrière-plan.
-    draw_asteroids(asteroids,asteroid_texture); // Dessine les astéroïdes.
-    draw_spaceship(spaceship,WHITE); // Dessine le vaisseau.
-    draw_shield_level(spaceship);
-    draw_missiles(missiles);
+async fn draw(world:&GameWorld,particles:&ParticleSystem,alpha:f32,background_texture:&Texture2D) {
+    draw_background(background_texture,world.screen_size()); // Dessine l'arrière-plan.
+    draw_asteroids(world.asteroids(),alpha,world.screen_size()); // Dessine les astéroïdes.
+    draw_particles(particles); // Dessine les débris et la flamme du réacteur.
+    draw_spaceship(world.spaceship(),alpha,world.screen_size(),WHITE); // Dessine le vaisseau.
+    draw_shield_level(world.spaceship());
+    draw_score(world.score());
+    draw_hyperspace(world.spaceship());
+    draw_weapon(world.weapon(),world.missiles().len());
+    draw_wave(world.wave());
+    draw_effects(world.effects());
+    draw_power_ups(world.power_ups(),alpha);
+    draw_missiles(world.missiles(),alpha,WHITE);
+    draw_saucers(world.saucers(),alpha);
+    draw_missiles(world.enemy_missiles(),alpha,RED);
+    if world.next_wave_in().is_some() {
+        draw_wave_transition(world.wave() + 1,world.screen_size());
+    }
 }
 
 ///Remplit l'arrière-plan en affichant l'image Fond d'écran .
@@ -35,8 +43,9 @@ async fn draw(asteroids: &Vec<Asteroid>,spaceship:&Spaceship,missiles:&Vec<Missi
 /// # Arguments
 ///
 /// * `texture` - Une référence à Texture2D.
-fn draw_background(texture:&Texture2D) {
-    let (screen_width, screen_height) = (screen_width(), screen_height());
+/// * `world_size` - La taille du monde de jeu.
+fn draw_background(texture:&Texture2D,world_size:(f32,f32)) {
+    let (screen_width, screen_height) = world_size;
     draw_texture_ex(
         texture,
         0.0,
@@ -49,44 +58,104 @@ fn draw_background(texture:&Texture2D) {
     );
 }
 
-/// Affiche le game over et Restart 
+/// Affiche le game over, le tableau des meilleurs scores et Restart 
 /// 
 /// 
 /// # Arguments
 ///
 /// * `background_texture` - Une référence à Texture2D.
-/// * `win` - Un boolean indiquant le cas d'une victoire.
+/// * `world` - La partie terminée.
+/// * `high_scores` - Le tableau des meilleurs scores.
+/// * `initials` - Les initiales en cours de saisie, si le score entre dans le tableau.
 /// 
-async fn game_state(background_texture:&Texture2D,win:bool){
+async fn game_state(background_texture:&Texture2D,world:&GameWorld,high_scores:&HighScores,initials:Option<&str>){
 
-    draw_background(background_texture);
+    draw_background(background_texture,world.screen_size());
+    let (width, height) = world.screen_size();
+    let x = width / 2.0 - 100.0;
+    let y = height / 2.0 - 150.0;
     // Affichage de l'écran de l'etat de jeu et Restart 
-    match win {
-        false=> {
-            draw_text(
-                "GAME OVER",
-                screen_width() / 2.0 - 100.0,
-                screen_height() / 2.0 - 50.0,
-                70.0,
-                WHITE,);
-        } ,
-        true => {
-            draw_text(
-            "YOU WIN ",
-            screen_width() / 2.0 - 100.0,
-            screen_height() / 2.0 - 50.0,
-            70.0,
-            WHITE,);
+    draw_text("GAME OVER", x, y, 70.0, WHITE,);
+
+    let text = format!(" Score : {}   Wave : {}", world.score(), world.wave());
+    draw_text(&text, x, y + 40.0, 30.0, WHITE,);
+
+    match initials {
+        // Saisie des initiales style borne d'arcade
+        Some(initials) => {
+            let text = format!(" NEW HIGH SCORE ! Initials : {:_<3}", initials);
+            draw_text(&text, x, y + 80.0, 30.0, YELLOW,);
+        },
+        None => {
+            draw_text(" Press 'R' to Restart ", x, y + 80.0, 30.0, WHITE,);
         },
     }
-   
+
+    let text = format!(" Seed : {}", world.seed());
+    draw_text(&text, x, y + 105.0, 20.0, GRAY,);
+
+    draw_text(" HIGH SCORES", x, y + 145.0, 30.0, WHITE,);
+    for (rank, entry) in high_scores.entries().iter().enumerate() {
+        let text = format!("{:>3}. {:<3} {:>8}  wave {}", rank + 1, entry.initials, entry.score, entry.wave);
+        draw_text(&text, x, y + 170.0 + rank as f32 * 22.0, 24.0, WHITE,);
+    }
+}
+/// Affiche le score de la partie à côté du niveau de bouclier.
+/// 
+/// # Arguments
+///
+/// * `score` - Le score de la partie.
+/// 
+fn draw_score(score:u32){
+    let text = format!("Score : {}", score);
     draw_text(
-        " Press 'R' to Restart ",
-        screen_width() / 2.0 - 100.0,
-        screen_height() / 2.0,
+        &text,
+        260.0,
+        20.0,
         30.0,
-        WHITE,);
-
+        WHITE,
+    );
+}
+/// Affiche le numéro de la vague en cours.
+/// 
+/// # Arguments
+///
+/// * `wave` - Le numéro de la vague.
+/// 
+fn draw_wave(wave:u32){
+    let text = format!("Wave : {}", wave);
+    draw_text(
+        &text,
+        10.0,
+        60.0,
+        30.0,
+        WHITE,
+    );
+}
+/// Affiche l'écran de transition annonçant la vague suivante.
+/// 
+/// # Arguments
+///
+/// * `next_wave` - Le numéro de la vague à venir.
+/// * `world_size` - La taille du monde de jeu.
+/// 
+fn draw_wave_transition(next_wave:u32,world_size:(f32,f32)){
+    let (width, height) = world_size;
+    let text = format!("WAVE {}", next_wave);
+    draw_text(
+        &text,
+        width / 2.0 - 80.0,
+        height / 2.0 - 50.0,
+        70.0,
+        WHITE,
+    );
+    draw_text(
+        " Get ready ",
+        width / 2.0 - 60.0,
+        height / 2.0,
+        30.0,
+        WHITE,
+    );
 }
 /// Affiche le niveau de visseau .
 /// 
@@ -104,6 +173,48 @@ fn draw_shield_level(spaceship:&Spaceship){
         WHITE,                         
     );
 }
+/// Affiche la disponibilité du saut en hyperespace, à droite du score.
+/// 
+/// # Arguments
+///
+/// * `spaceship` - Une référence à Spaceship (le Visseau).
+/// 
+fn draw_hyperspace(spaceship:&Spaceship){
+    let cooldown = spaceship.get_hyperspace_cooldown();
+    let (text, color) = if cooldown > 0.0 {
+        (format!("Hyperspace : {:.0}s", cooldown.ceil()), GRAY)
+    } else {
+        ("Hyperspace : ready".to_string(), WHITE)
+    };
+    draw_text(
+        &text,
+        500.0,
+        20.0,
+        30.0,
+        color,
+    );
+}
+/// Affiche l'état de l'arme sous le saut en hyperespace : les missiles en vol
+/// par rapport au plafond, et la jauge de chaleur (rouge en surchauffe).
+/// 
+/// # Arguments
+///
+/// * `weapon` - Une référence à l'arme du vaisseau.
+/// * `missiles_in_flight` - Le nombre de missiles du joueur en vol.
+/// 
+fn draw_weapon(weapon:&Weapon,missiles_in_flight:usize){
+    let text = format!("Missiles : {}/{}", missiles_in_flight, weapon.max_missiles());
+    let color = if weapon.can_fire(missiles_in_flight) { WHITE } else { GRAY };
+    draw_text(&text, 500.0, 45.0, 24.0, color);
+
+    let (x, y, width, height) = (500.0, 52.0, 150.0, 10.0);
+    let fill = if weapon.is_overheated() { RED } else { ORANGE };
+    draw_rectangle(x, y, width * weapon.heat(), height, fill);
+    draw_rectangle_lines(x, y, width, height, 2.0, WHITE);
+    if weapon.is_overheated() {
+        draw_text("OVERHEAT", x + width + 10.0, y + height, 20.0, RED);
+    }
+}
 /// Affiche le temps ecoulé dans le jeu 
 /// 
 /// # Arguments
@@ -125,115 +236,500 @@ fn show_elapsed_time(t:Duration){
         WHITE,                         
     );
 }
+/// Affiche un message d'état (sauvegarde, chargement) en bas de l'écran.
+/// 
+/// # Arguments
+///
+/// * `text` - Le message à afficher.
+/// * `world_size` - La taille du monde de jeu.
+/// 
+fn draw_status(text:&str,world_size:(f32,f32)){
+    draw_text(
+        text,
+        10.0,
+        world_size.1 - 20.0,
+        25.0,
+        YELLOW,
+    );
+}
 /// Dessine chaque messile tiré sur l'écran.
 ///
 /// # Arguments
 ///
-/// * `missiles` - Une référence à un vecteur de missiles à dessiner.
+/// * `missiles` - Une référence à une tranche de missiles à dessiner.
+/// * `alpha` - Le facteur d'interpolation entre le pas précédent et le pas actuel.
+/// * `c` - La couleur des missiles (ceux des soucoupes sont rouges).
 /// 
-fn draw_missiles(missiles:&Vec<Missile>){
+fn draw_missiles(missiles:&[Missile],alpha:f32,c:Color){
     for missile in missiles{
         if missile.is_active(){
-            draw_circle(missile.get_position().x, missile.get_position().y, 4.0, WHITE);
+            let position = missile.get_interpolated_position(alpha);
+            draw_circle(position.x, position.y, 4.0, c);
         }
     }
 }
-/// Dessine chaque astéroïde sur l'écran.
+/// Retourne la couleur et la lettre d'un bonus.
 ///
 /// # Arguments
 ///
-/// * `asteroids` - Une référence à un vecteur d'astéroïdes à dessiner.
-/// * `asteroids_texture` - Une référence à Texture2D.
+/// * `kind` - Le type de bonus.
+fn power_up_style(kind:PowerUpKind) -> (Color,&'static str) {
+    match kind {
+        PowerUpKind::MultiShot => (SKYBLUE, "M"),
+        PowerUpKind::RapidFire => (ORANGE, "R"),
+        PowerUpKind::ShieldRecharge => (GREEN, "S"),
+        PowerUpKind::Piercing => (VIOLET, "P"),
+    }
+}
+/// Dessine chaque bonus à ramasser : un cercle marqué de sa lettre,
+/// qui clignote quand il va disparaître.
+///
+/// # Arguments
+///
+/// * `power_ups` - Une référence à une tranche de bonus à dessiner.
+/// * `alpha` - Le facteur d'interpolation entre le pas précédent et le pas actuel.
+/// 
+fn draw_power_ups(power_ups:&[PowerUp],alpha:f32){
+    for power_up in power_ups {
+        if power_up.lifetime() < 2.0 && (power_up.lifetime() * 10.0) as i32 % 2 == 1 {
+            continue;
+        }
+        let (color, letter) = power_up_style(power_up.kind());
+        let size = power_up.get_size();
+        let position = power_up.get_interpolated_position(alpha);
+        draw_circle_lines(position.x, position.y, size, 2.0, color);
+        draw_text(letter, position.x - size / 2.0, position.y + size / 2.0, size * 2.0, color);
+    }
+}
+/// Affiche les bonus temporaires actifs et leur temps restant, sous le numéro de vague.
+///
+/// # Arguments
+///
+/// * `effects` - Les bonus temporaires actifs.
 /// 
-fn draw_asteroids(asteroids: &Vec<Asteroid>,asteroid_texture:&Texture2D) {
+fn draw_effects(effects:&ActiveEffects){
+    let names = [
+        (PowerUpKind::MultiShot, "Multi-shot"),
+        (PowerUpKind::RapidFire, "Rapid fire"),
+        (PowerUpKind::Piercing, "Piercing"),
+    ];
+    let mut y = 80.0;
+    for (kind, name) in names {
+        if effects.is_active(kind) {
+            let text = format!("{} : {:.0}s", name, effects.remaining(kind).ceil());
+            draw_text(&text, 10.0, y, 24.0, power_up_style(kind).0);
+            y += 20.0;
+        }
+    }
+}
+/// Dessine chaque soucoupe ennemie sur l'écran : une coque plate surmontée d'un dôme.
+///
+/// # Arguments
+///
+/// * `saucers` - Une référence à une tranche de soucoupes à dessiner.
+/// * `alpha` - Le facteur d'interpolation entre le pas précédent et le pas actuel.
+/// 
+fn draw_saucers(saucers:&[Saucer],alpha:f32){
+    for saucer in saucers {
+        let size = saucer.get_size();
+        let position = saucer.get_interpolated_position(alpha);
+        let hull = [
+            vec2(-size, 0.0),
+            vec2(-size / 2.0, size / 3.0),
+            vec2(size / 2.0, size / 3.0),
+            vec2(size, 0.0),
+            vec2(size / 2.0, -size / 3.0),
+            vec2(-size / 2.0, -size / 3.0),
+        ];
+        for i in 0..hull.len() {
+            let (a, b) = (hull[i] + position, hull[(i + 1) % hull.len()] + position);
+            draw_line(a.x, a.y, b.x, b.y, 2.0, GREEN);
+        }
+        draw_line(position.x - size, position.y, position.x + size, position.y, 2.0, GREEN);
+        draw_circle_lines(position.x, position.y - size / 3.0, size / 3.0, 2.0, GREEN);
+    }
+}
+/// Dessine chaque astéroïde sur l'écran : son contour irrégulier, tel qu'il est utilisé
+/// pour les collisions. Un astéroïde à cheval sur un bord est dessiné des deux côtés.
+///
+/// # Arguments
+///
+/// * `asteroids` - Une référence à une tranche d'astéroïdes à dessiner.
+/// * `alpha` - Le facteur d'interpolation entre le pas précédent et le pas actuel.
+/// * `screen_size` - La taille de l'écran de jeu.
+/// 
+fn draw_asteroids(asteroids: &[Asteroid],alpha:f32,screen_size:(f32,f32)) {
     for asteroid in asteroids {
-        let size = asteroid.get_size();
-
-        // Dessiner l'image de l'astéroïde en redimensionnant selon sa taille
-        draw_texture_ex(
-            asteroid_texture,                       
-            asteroid.get_position().x - size*1.8,                 
-            asteroid.get_position().y - size*2.0,                 
-            WHITE,                                   
-            DrawTextureParams {
-                dest_size: Some(vec2(size*4.0, size*4.0)),  // Redimensionner l'image en fonction de la taille de l'astéroïde
-                ..Default::default()
+        let radius = asteroid.get_size() * Asteroid::OUTLINE_MAX_RADIUS;
+        for position in torus::images(asteroid.get_interpolated_position(alpha), radius, screen_size) {
+            let outline = polygon::transform(asteroid.get_shape(), position, asteroid.get_rotation());
+
+            // Remplir l'astéroïde en triangles autour de son centre, puis tracer son contour
+            for (i, &a) in outline.iter().enumerate() {
+                let b = outline[(i + 1) % outline.len()];
+                draw_triangle(position, a, b, Color::new(0.25, 0.22, 0.2, 1.0));
+                draw_line(a.x, a.y, b.x, b.y, 2.0, LIGHTGRAY);
+            }
+        }
+    }
+}
+/// Dessine les particules, de plus en plus transparentes en vieillissant : des points,
+/// ou des segments pour les morceaux du vaisseau.
+///
+/// # Arguments
+///
+/// * `particles` - Les particules à dessiner.
+///
+fn draw_particles(particles:&ParticleSystem){
+    for particle in particles.iter() {
+        let color = particle.faded_color();
+        let position = particle.position;
+        match particle.angle {
+            Some(angle) => {
+                let half = Vec2::from_angle(angle) * particle.size;
+                let (a, b) = (position - half, position + half);
+                draw_line(a.x, a.y, b.x, b.y, 2.0, color);
             },
-        );
-        
+            None => draw_circle(position.x, position.y, particle.size, color),
+        }
     }
 }
 /// Dessine Le visseau sur l'écran.
+/// Le vaisseau qui attend de réapparaître ou saute en hyperespace n'est pas dessiné, et il clignote
+/// pendant son invulnérabilité.
 ///
 /// # Arguments
 ///
 /// * `spaceship` - Une référence à Spaceship.
+/// * `alpha` - Le facteur d'interpolation entre le pas précédent et le pas actuel.
+/// * `screen_size` - La taille de l'écran de jeu, pour dessiner le vaisseau des deux côtés d'un bord.
 /// * `c` - Une couleur de type 'Color' qui définit la couleur du vaisseau.
 /// 
-fn draw_spaceship(spaceship : &Spaceship,c:Color) {
-    let rotation_angle = spaceship.get_orientation();
-    let shape_points = [
-        vec2(0.0, -15.0),
-        vec2(10.0, 15.0),
-        vec2(-10.0, 15.0),
-    ];
-    let rotated_points:Vec<Vec2> = shape_points.iter()
-    .map(|&point| Vec2::from_angle(point.to_angle()+rotation_angle)*point.length()).collect();
-    draw_triangle_lines(
-        rotated_points[0]+spaceship.get_position(),
-        rotated_points[1]+spaceship.get_position(),
-        rotated_points[2]+spaceship.get_position(),
-        3.0,
-        c,);
+fn draw_spaceship(spaceship : &Spaceship,alpha:f32,screen_size:(f32,f32),c:Color) {
+    // Clignote 5 fois par seconde au rythme du temps d'invulnérabilité restant
+    let blink_off = (spaceship.get_invulnerability() * 10.0) as i32 % 2 == 1;
+    if !spaceship.is_visible() || blink_off {
+        return;
+    }
+    let position = spaceship.get_interpolated_position(alpha);
+    for position in torus::images(position, Spaceship::RADIUS, screen_size) {
+        let outline = polygon::transform(&Spaceship::SHAPE, position, spaceship.get_orientation());
+        draw_triangle_lines(
+            outline[0],
+            outline[1],
+            outline[2],
+            3.0,
+            c,);
+    }
 }
 
-/// Gère l'entrée de l'utilisateur.
+/// Indique si une action est en cours : une de ses touches ou un de ses boutons est enfoncé.
+///
 /// # Arguments
 ///
-/// * `spaceship` - Une référence au visseau.
-/// * `missiles` - Une référence mutable à un vecteur de missiles tiré.
-/// * `missile_sound` - Une référence à Sound , represente le son du tire.
-/// 
+/// * `controls` - Les liaisons entre actions et touches.
+/// * `action` - L'action à tester.
+fn action_down(controls:&Controls,action:Action) -> bool {
+    controls.bindings(action).iter().any(|&binding| match binding {
+        Binding::Key(key) => is_key_down(key),
+        Binding::Mouse(button) => is_mouse_button_down(button),
+    })
+}
+
+/// Indique si une action vient d'être déclenchée : une de ses touches ou un de ses
+/// boutons a été enfoncé pendant cette image.
+///
+/// # Arguments
+///
+/// * `controls` - Les liaisons entre actions et touches.
+/// * `action` - L'action à tester.
+fn action_pressed(controls:&Controls,action:Action) -> bool {
+    controls.bindings(action).iter().any(|&binding| match binding {
+        Binding::Key(key) => is_key_pressed(key),
+        Binding::Mouse(button) => is_mouse_button_pressed(button),
+    })
+}
+
+/// Commandes du joueur lues au clavier et à la souris.
+struct KeyboardInput {
+    controls: Controls,       // Liaisons entre actions et touches, modifiables par le menu F1
+    fire_pending: bool,       // Un appui bref sur le tir est conservé jusqu'au prochain pas
+    hyperspace_pending: bool, // De même pour l'hyperespace
+}
+
+impl KeyboardInput {
+    /// Crée la source clavier.
+    ///
+    /// # Arguments
+    ///
+    /// * `controls` - Les liaisons entre actions et touches.
+    fn new(controls:Controls) -> Self {
+        Self { controls, fire_pending: false, hyperspace_pending: false }
+    }
+
+    /// Relève les appuis de cette image, pour qu'un appui plus bref qu'un pas de
+    /// simulation ne soit pas perdu. A appeler à chaque image.
+    fn poll(&mut self) {
+        self.fire_pending |= action_pressed(&self.controls, Action::Fire);
+        self.hyperspace_pending |= action_pressed(&self.controls, Action::Hyperspace);
+    }
+}
+
+impl InputSource for KeyboardInput {
+    fn next_input(&mut self, _world:&GameWorld) -> Option<PlayerInput> {
+        let controls = &self.controls;
+        let input = PlayerInput {
+            thrust: action_down(controls, Action::Thrust),            // Poussée
+            back_thrust: action_down(controls, Action::Brake),        // Rétro-poussée
+            rotate_left: action_down(controls, Action::RotateLeft),   // Rotation à gauche
+            rotate_right: action_down(controls, Action::RotateRight), // Rotation à droite
+            fire: self.fire_pending || action_down(controls, Action::Fire), // Tir des missiles
+            hyperspace: self.hyperspace_pending || action_down(controls, Action::Hyperspace), // Saut en hyperespace
+        };
+        self.fire_pending = false;
+        self.hyperspace_pending = false;
+        Some(input)
+    }
+}
+
+/// État du menu des commandes (F1).
+struct ControlsMenu {
+    selected: usize,        // Indice de l'action choisie dans `Action::ALL`
+    capture: Option<bool>,  // En attente d'une touche : `Some(true)` la remplace, `Some(false)` l'ajoute
+}
+
+/// Retourne la touche ou le bouton de souris enfoncé pendant cette image, parmi
+/// ceux qui peuvent être liés à une action.
+fn pressed_binding() -> Option<Binding> {
+    if let Some(key) = get_last_key_pressed().filter(|key| Binding::KEYS.contains(key)) {
+        return Some(Binding::Key(key));
+    }
+    Binding::MOUSE_BUTTONS.iter()
+        .find(|(button, _)| is_mouse_button_pressed(*button))
+        .map(|&(button, _)| Binding::Mouse(button))
+}
+
+/// Traite le clavier dans le menu des commandes.
+///
+/// # Arguments
+///
+/// * `menu` - L'état du menu.
+/// * `controls` - Les liaisons à modifier.
+///
 /// # Returns
 ///
-/// * `bool` - Retourne vrai si la touche Échap (Escape) est enfoncée.
-fn handle_input(spaceship:&mut Spaceship,missiles:&mut Vec<Missile>,missile_sound: &Sound) -> bool {
-    if is_key_down(KeyCode::Escape) {
-        return true; 
-    }
-    // Gestion de la poussée (touche flèche haut)
-    if is_key_down(KeyCode::Up){
-        spaceship.activate_thrust();
-        // Changer la couleur du vaisseau afin de montrer l'effet de la poussée
-        draw_spaceship(spaceship, ORANGE);
-    }
-    // Gestion de la rétro-poussée (touche flèche bas)
-    if is_key_down(KeyCode::Down){
-        spaceship.back_thrust();
-    }
-    // Gestion de la rotation à gauche (touche flèche gauche)
-    if is_key_down(KeyCode::Left){
-        spaceship.left_rotation();
-    }
-    // Gestion de la rotation à droite (touche flèche droite)
-    if is_key_down(KeyCode::Right){
-        spaceship.right_rotation();
-    }
-    // Gestion des tirs des missiles(touche Espace)
-    // son 
-    if is_key_pressed(KeyCode::Space){
-        missiles.push(
-            Missile::new(
-                spaceship.get_position(),
-                spaceship.get_orientation(),
-                screen_size(),
-            )
-        );
-        play_sounds(missile_sound);
+/// * `bool` - Retourne vrai quand le menu est refermé par F1. Il reste ouvert tant
+///   que les liaisons sont invalides (une action sans touche, après qu'une autre
+///   action lui a pris la dernière), pour ne jamais enregistrer un fichier que le
+///   prochain lancement refuserait.
+fn edit_controls(menu:&mut ControlsMenu,controls:&mut Controls) -> bool {
+    let action = Action::ALL[menu.selected];
+    if let Some(replace) = menu.capture {
+        // F1 annule la saisie sans fermer le menu
+        if is_key_pressed(KeyCode::F1) {
+            menu.capture = None;
+        }
+        else if let Some(binding) = pressed_binding() {
+            controls.bind(action, binding, replace);
+            menu.capture = None;
+        }
+        return false;
+    }
+    if is_key_pressed(KeyCode::F1) {
+        return controls.validate().is_ok();
+    }
+    if is_key_pressed(KeyCode::Up) {
+        menu.selected = (menu.selected + Action::ALL.len() - 1) % Action::ALL.len();
+    }
+    if is_key_pressed(KeyCode::Down) {
+        menu.selected = (menu.selected + 1) % Action::ALL.len();
+    }
+    if is_key_pressed(KeyCode::Enter) {
+        // Maj+Entrée ajoute une touche au lieu de remplacer celles de l'action
+        let add = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+        menu.capture = Some(!add);
+    }
+    if is_key_pressed(KeyCode::Backspace) {
+        controls.reset(action);
+    }
+    if is_key_pressed(KeyCode::Tab) {
+        *controls = Controls::from_scheme(controls.scheme().next());
     }
     false
 }
 
+/// Dessine le menu des commandes par-dessus la partie.
+///
+/// # Arguments
+///
+/// * `menu` - L'état du menu.
+/// * `controls` - Les liaisons à afficher.
+/// * `world_size` - La taille du monde de jeu.
+fn draw_controls_menu(menu:&ControlsMenu,controls:&Controls,world_size:(f32,f32)){
+    draw_rectangle(0.0, 0.0, world_size.0, world_size.1, Color::new(0.0, 0.0, 0.0, 0.75));
+    draw_text("COMMANDES", world_size.0 / 2.0 - 90.0, 70.0, 40.0, WHITE);
+    draw_text(format!("Schema : {:?} (Tab pour changer)", controls.scheme()), 80.0, 120.0, 25.0, LIGHTGRAY);
+    let mut y = 170.0;
+    for (i, action) in Action::ALL.into_iter().enumerate() {
+        let color = if i == menu.selected { YELLOW } else { WHITE };
+        let bindings = if i == menu.selected && menu.capture.is_some() {
+            "appuyez sur une touche...".to_string()
+        } else {
+            controls.bindings(action).iter().map(|b| b.to_string()).collect::<Vec<_>>().join(", ")
+        };
+        draw_text(action.label(), 80.0, y, 28.0, color);
+        draw_text(&bindings, 330.0, y, 28.0, color);
+        y += 35.0;
+    }
+    draw_text("Entree : remplacer   Maj+Entree : ajouter   Retour : par defaut", 80.0, y + 30.0, 20.0, LIGHTGRAY);
+    match controls.validate() {
+        Ok(()) => draw_text("F1 : enregistrer et fermer", 80.0, y + 55.0, 20.0, LIGHTGRAY),
+        Err(e) => draw_text(format!("{} (F1 impossible)", e), 80.0, y + 55.0, 20.0, RED),
+    };
+}
+
+/// Options de la ligne de commande.
+struct Options {
+    seed: Option<u64>,       // Graine imposée par `--seed`, aléatoire sinon
+    record: Option<PathBuf>, // Fichier où enregistrer la partie (`--record`)
+    replay: Option<PathBuf>, // Fichier de la partie à rejouer (`--replay`)
+    config: Option<PathBuf>, // Fichier de réglages (`--config`), `DEFAULT_CONFIG_PATH` sinon
+    autopilot: Option<Skill>, // Niveau du pilote automatique qui joue à la place du joueur (`--autopilot`)
+}
+
+/// Lit les options de la ligne de commande.
+///
+/// # Arguments
+///
+/// * `args` - Les arguments du programme, sans le nom de l'exécutable.
+///
+/// # Returns
+///
+/// Les options lues, ou un message d'erreur si un argument est invalide.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options { seed: None, record: None, replay: None, config: None, autopilot: None };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                let value = args.next().ok_or("--seed attend une valeur")?;
+                let seed = value.parse().map_err(|_| format!("graine invalide : {}", value))?;
+                options.seed = Some(seed);
+            },
+            "--record" => options.record = Some(args.next().ok_or("--record attend un fichier")?.into()),
+            "--replay" => options.replay = Some(args.next().ok_or("--replay attend un fichier")?.into()),
+            "--config" => options.config = Some(args.next().ok_or("--config attend un fichier")?.into()),
+            "--autopilot" => {
+                let value = args.next().ok_or("--autopilot attend un niveau")?;
+                let skill = Skill::from_name(&value).ok_or_else(|| format!("niveau inconnu : {} (beginner, intermediate ou expert)", value))?;
+                options.autopilot = Some(skill);
+            },
+            _ => return Err(format!("option inconnue : {}", arg)),
+        }
+    }
+    Ok(options)
+}
+
+/// Fichier de réglages lu au démarrage quand `--config` n'est pas donné.
+const DEFAULT_CONFIG_PATH: &str = "assets/config.toml";
+
+/// Retourne le fichier de réglages à lire.
+///
+/// # Arguments
+///
+/// * `path` - Le fichier donné par `--config`, s'il y en a un.
+///
+/// # Returns
+///
+/// Le fichier donné, sinon `DEFAULT_CONFIG_PATH` s'il existe.
+fn config_path(path: &Option<PathBuf>) -> Option<PathBuf> {
+    match path {
+        Some(path) => Some(path.clone()),
+        None if std::path::Path::new(DEFAULT_CONFIG_PATH).exists() => Some(PathBuf::from(DEFAULT_CONFIG_PATH)),
+        None => None,
+    }
+}
+
+/// Charge les réglages du jeu.
+///
+/// # Arguments
+///
+/// * `path` - Le fichier donné par `--config`, s'il y en a un.
+///
+/// # Returns
+///
+/// Les réglages lus, les réglages par défaut si aucun fichier n'est donné et que
+/// `DEFAULT_CONFIG_PATH` n'existe pas, ou un message d'erreur si le fichier est invalide.
+fn load_config(path: &Option<PathBuf>) -> Result<GameConfig, String> {
+    match config_path(path) {
+        Some(path) => GameConfig::load(&path).map_err(|e| format!("réglages {} : {}", path.display(), e)),
+        None => Ok(GameConfig::default()),
+    }
+}
+
+/// Charge les commandes, lues dans la section `[controls]` du fichier de réglages
+/// puis dans `PLAYER_CONTROLS_PATH`, où le menu F1 enregistre celles du joueur.
+///
+/// # Arguments
+///
+/// * `path` - Le fichier donné par `--config`, s'il y en a un.
+///
+/// # Returns
+///
+/// Les commandes lues, celles du schéma par défaut sans fichier, ou un message
+/// d'erreur si une section est invalide.
+fn load_controls(path: &Option<PathBuf>) -> Result<Controls, String> {
+    let controls = match config_path(path) {
+        Some(path) => Controls::load(&path).map_err(|e| format!("commandes {} : {}", path.display(), e))?,
+        None => Controls::default(),
+    };
+    controls.load_over(PLAYER_CONTROLS_PATH).map_err(|e| format!("commandes {} : {}", PLAYER_CONTROLS_PATH, e))
+}
+
+/// Fichier des commandes modifiées par le menu F1, à côté des meilleurs scores,
+/// pour ne pas réécrire le fichier de réglages livré avec le jeu.
+const PLAYER_CONTROLS_PATH: &str = "controls.toml";
+
+/// Ecrit l'enregistrement de la partie si `--record` a été demandé.
+///
+/// # Arguments
+///
+/// * `path` - Le fichier de destination, s'il y en a un.
+/// * `recording` - L'enregistrement de la partie en cours, s'il est encore valide.
+fn save_recording(path: &Option<PathBuf>, recording: &Option<Replay>) {
+    if let (Some(path), Some(recording)) = (path, recording) {
+        if let Err(e) = recording.save(path) {
+            eprintln!("impossible d'enregistrer le replay {} : {}", path.display(), e);
+        }
+    }
+}
+
+/// Fichier du tableau des meilleurs scores.
+const HIGH_SCORES_PATH: &str = "highscores.json";
+
+/// Met à jour les initiales en cours de saisie à partir du clavier.
+///
+/// # Arguments
+///
+/// * `initials` - Les initiales déjà saisies.
+///
+/// # Returns
+///
+/// * `bool` - Retourne vrai quand la saisie est validée par Entrée.
+fn edit_initials(initials:&mut String) -> bool {
+    while let Some(c) = get_char_pressed() {
+        if c.is_ascii_alphanumeric() && initials.len() < HighScores::INITIALS_LEN {
+            initials.push(c.to_ascii_uppercase());
+        }
+    }
+    if is_key_pressed(KeyCode::Backspace) {
+        initials.pop();
+    }
+    is_key_pressed(KeyCode::Enter) && !initials.is_empty()
+}
+
+/// Fichier de la sauvegarde rapide (F5 pour sauvegarder, F9 pour recharger).
+const QUICKSAVE_PATH: &str = "quicksave.json";
+
 /// Lance le son des differents évenements .
 ///
 /// # Arguments
@@ -248,155 +744,271 @@ fn play_sounds (s:&Sound) {
         },
     );
 }
-/// Met à jour de tous les composants du modele de jeu.
-///
-/// # Arguments
-///
-/// * `asteroids` - Une référence mutable à un vecteur d'astéroïdes.
-/// * `spaceship` - Une référence mutable au visseau . 
-/// * `missiles` - Une référence mutable à un vecteur de missiles tiré.
-/// * `collision_missile_sound` - Une référence a Sound.
-/// * `collision_ship_sound` - Une référence a Sound.
-fn update_model(asteroids: &mut Vec<Asteroid>,spaceship:&mut Spaceship,missiles:&mut Vec<Missile>,
-    collision_missile_sound: &Sound,collision_ship_sound: &Sound,) {
-    let mut new_asteroids = Vec::new();
-    for asteroid in asteroids.iter_mut() {
-        asteroid.update(); // Déplace chaque astéroïde.
-        // Vérifier la collision entre le vaisseau et l'astéroïde
-        let mut collision_ship = false;
-        if spaceship.check_collision(asteroid) {
-            collision_ship = true;
-            play_sounds(collision_ship_sound);// son lié a la colission entre visseau et un asteroid
-            if let Some((a1, a2)) = asteroid.split_asteroid() {
-
-                // Diviser l asteroid en deux si il y a une collision entre le vaisseau et l asteroid
-                new_asteroids.push(a1);
-                new_asteroids.push(a2);
-            }
-            // Si le vaisseau est détruit suite à la collision, on quitte la boucle
-            if spaceship.is_destroyed() {
-                break;
-            }
-        }
-        // Parcourir les missiles pour vérifier les collisions
-        let mut missile_hit = false;
-        for missile in missiles.iter_mut() {
-            missile.update(); // Mettre à jour la position du missile
-        
-            // Vérifier la collision entre le missile et l'astéroïde
-            if missile.check_collision(asteroid) {
-                missile_hit = true; // Indiquer que le missile a touché l'astéroïde
-                //et mettre du son 
-                play_sounds(collision_missile_sound);
-                break;
-            }
-        }
-        
-        // Si un missile a touché l'astéroïde, le diviser et le supprimer
-        if missile_hit {
-            if let Some((a1, a2)) = asteroid.split_asteroid() {
-                // Ajouter les nouveaux astéroïdes s'ils sont divisés
-                new_asteroids.push(a1);
-                new_asteroids.push(a2);
-            }
-        } else if !collision_ship {
-            // Si l'astéroïde n'a pas été touché et pas de collision avec le vaisseau, le conserver
-            new_asteroids.push(asteroid.clone());
-        }
+/// Réglages de la fenêtre : elle s'ouvre à la taille du monde de jeu et peut être redimensionnée.
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "ASTEROID GAME".to_string(),
+        window_width: GameWorld::SIZE.0 as i32,
+        window_height: GameWorld::SIZE.1 as i32,
+        window_resizable: true,
+        ..Default::default()
     }
-    // Mettre à jour la position du missile
-    for missile in missiles.iter_mut() {
-        missile.update(); 
-    }
-    missiles.retain(|m| m.is_active());
-
-    // Mettre à jour la liste des astéroïdes  et le visseau 
-    *asteroids = new_asteroids;
-    spaceship.update();
-    
 }
-
-/// Initialise les objets de jeu (Modele) 
+/// Crée la caméra qui affiche le monde de jeu dans la fenêtre : à la plus grande taille
+/// qui tient sans déformation, centrée entre des bandes noires.
+///
 /// # Arguments
 ///
-/// * `spaceship` - Une référence mutable Spaceship (au visseau) .
-/// * `asteroids` - Une référence mutable à un vecteur d'astéroïdes.
-/// * `missiles` - Une référence mutable à un vecteur de missiles.
-/// 
-fn init_game(
-    spaceship: &mut Spaceship,
-    asteroids: &mut Vec<Asteroid>,
-    missiles: &mut Vec<Missile>,
-) {
-    *spaceship = Spaceship::new(screen_size()); // Réinitialise le vaisseau
-    asteroids.clear(); // Supprime tous les astéroïdes
-    // creation d'un vecteur de missiles initialement vide 
-    *missiles=Vec::new(); 
-    // creation de n(alea) astroieds
-    let n_asteroid = thread_rng().gen_range(4..=8); 
-    let mut vec_asteroids = Vec::new();
-    for _ in 0..n_asteroid {
-        vec_asteroids.push(asteroid::Asteroid::new(Asteroid::ASTEROID_INIT_SIZE,screen_size()));
-    } 
-    *asteroids = vec_asteroids; 
+/// * `world_size` - La taille du monde de jeu.
+fn world_camera(world_size:(f32,f32)) -> Camera2D {
+    let letterbox = Letterbox::fit(world_size, (screen_width(), screen_height()));
+    Camera2D {
+        viewport: Some(letterbox.gl_viewport(screen_dpi_scale())),
+        ..Camera2D::from_display_rect(Rect::new(0.0, 0.0, world_size.0, world_size.1))
+    }
 }
-
-
 /// Fonction principale du programme.
 ///
 /// Gère la boucle de jeu.
 /// 
-#[macroquad::main("ASTEROID GAME")] // Annotation pour exécuter la fonction principale avec macroquad.
+#[macroquad::main(window_conf)] // Annotation pour exécuter la fonction principale avec macroquad.
 async fn main() {
+    let options = parse_args(std::env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    });
+    let config = load_config(&options.config).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    });
+    let mut keyboard = KeyboardInput::new(load_controls(&options.config).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    }));
+    // Partie à rejouer : les commandes viennent du fichier et non du clavier
+    let mut playback = options.replay.as_ref().map(|path| Replay::load(path).unwrap_or_else(|e| {
+        eprintln!("impossible de lire le replay {} : {}", path.display(), e);
+        std::process::exit(2);
+    }));
+    // La graine imposée est réutilisée à chaque partie, sinon chaque partie en tire une nouvelle
+    let new_world = |playback: &Option<Replay>| match playback {
+        Some(replay) => replay.new_world(),
+        None => GameWorld::with_config(options.seed.unwrap_or_else(::rand::random), config),
+    };
+    // En mode démonstration (`--autopilot`), le pilote automatique joue chaque partie avec la graine de celle-ci
+    let new_autopilot = |world: &GameWorld| options.autopilot.map(|skill| Autopilot::new(skill, world.seed()));
+    // Un enregistrement n'est tenu que si `--record` a été demandé
+    let new_recording = |world: &GameWorld| options.record.as_ref().map(|_| Replay::new(world.seed(), *world.config()));
+
     // Charger les fichiers audio 
     let missile_sound = load_sound("assets/sounds/missile.wav").await.unwrap();
     let collision_missile_sound = load_sound("assets/sounds/collision_missile_asteroid.wav").await.unwrap();
     let collision_ship_sound = load_sound("assets/sounds/collision_ship_asteroid.wav").await.unwrap();
     let game_over_sound = load_sound("assets/sounds/game_over.wav").await.unwrap();
 
-    // init des boolean d'etat de jeu a faux 
-    let mut is_game_over = false;
-    let mut win =false;
     // Charger les Images
     let background_texture = load_texture("assets/images/background_space.png").await.unwrap();
-    let asteroid_texture = load_texture("assets/images/asteroid.png").await.unwrap();
     
-    // Création d'un vaisseau
-    let mut spaceship = Spaceship::new(screen_size());
-    // creation d'un vecteur de missiles initialement vide
-    let mut vec_asteroids = Vec::new();
-    let mut missiles=Vec::new();
-
-    init_game(&mut spaceship, &mut vec_asteroids, &mut missiles); 
-    let mut start_time = Instant::now(); // temps de debut 
+    // Création de la partie (vaisseau, astéroïdes et missiles)
+    let mut world = new_world(&playback);
+    let mut recording = new_recording(&world);
+    let mut autopilot = new_autopilot(&world);
+    // Un fichier illisible est mis de côté par `load` ; s'il n'a pas pu l'être, il n'est jamais écrasé
+    let (mut high_scores, high_scores_writable) = match HighScores::load(HIGH_SCORES_PATH) {
+        Ok(scores) => (scores, true),
+        Err(e) => {
+            eprintln!("impossible de lire les meilleurs scores {} : {}", HIGH_SCORES_PATH, e);
+            (HighScores::default(), !std::path::Path::new(HIGH_SCORES_PATH).exists())
+        },
+    };
+    // Initiales en cours de saisie quand le score de la partie entre dans le tableau
+    let mut initials: Option<String> = None;
+    // Message d'état affiché quelques secondes, avec l'instant de son apparition
+    let mut status: Option<(String, f64)> = None;
+    // La simulation avance par pas fixes, quelle que soit la fréquence d'affichage
+    let mut timestep = FixedTimestep::new(GameWorld::TICK);
+    // Partie en pause, et menu des commandes ouvert par F1 (qui met aussi la partie en pause)
+    let mut paused = false;
+    let mut menu: Option<ControlsMenu> = None;
+    let mut fullscreen = false;
+    // Effets visuels, hors de la simulation
+    let mut particles = ParticleSystem::new(ParticleSystem::CAPACITY, ::rand::random());
     // Boucle principale du jeu.
     loop {
-        // gerer le restart apres une defaite ou une victoire
-        if is_game_over || win {
-            game_state(&background_texture,win).await;
-            if is_key_pressed(KeyCode::R) {
-                init_game(&mut spaceship, &mut vec_asteroids, &mut missiles);
-                is_game_over = false;
-                win=false;
-                start_time=Instant::now() ;
+        // Plein écran avec F11
+        if is_key_pressed(KeyCode::F11) {
+            fullscreen = !fullscreen;
+            set_fullscreen(fullscreen);
+        }
+        // Recharger la sauvegarde rapide (sauf pendant un replay)
+        if is_key_pressed(KeyCode::F9) && playback.is_none() {
+            let message = match load_game(QUICKSAVE_PATH) {
+                Ok(loaded) => {
+                    world = loaded;
+                    particles.clear();
+                    // Un replay part du début de la partie : il ne peut plus être tenu
+                    if recording.take().is_some() {
+                        "Partie chargée, enregistrement du replay interrompu".to_string()
+                    } else {
+                        "Partie chargée".to_string()
+                    }
+                },
+                Err(e) => format!("Chargement impossible : {}", e),
+            };
+            status = Some((message, get_time()));
+        }
+        // Tout est dessiné dans les coordonnées du monde, mises à l'échelle de la fenêtre
+        clear_background(BLACK);
+        set_camera(&world_camera(world.screen_size()));
+        // gerer le restart apres une defaite
+        if world.is_game_over() && !particles.is_empty() {
+            // Laisser le vaisseau finir d'éclater avant l'écran de fin de partie
+            particles.update(get_frame_time(), world.screen_size());
+            draw(&world,&particles,1.0,&background_texture).await;
+        }
+        else if world.is_game_over() && autopilot.is_some() && playback.is_none() {
+            // En démonstration, une nouvelle partie commence dès que le vaisseau a fini d'éclater
+            world = new_world(&playback);
+            recording = new_recording(&world);
+            autopilot = new_autopilot(&world);
+            draw(&world,&particles,1.0,&background_texture).await;
+        }
+        else if world.is_game_over() {
+            game_state(&background_texture,&world,&high_scores,initials.as_deref()).await;
+            if let Some(entered) = initials.as_mut() {
+                if edit_initials(entered) {
+                    high_scores.insert(HighScore { initials: entered.clone(), score: world.score(), wave: world.wave() });
+                    if !high_scores_writable {
+                        status = Some((format!("Meilleurs scores non enregistrés : {} est illisible", HIGH_SCORES_PATH), get_time()));
+                    }
+                    else if let Err(e) = high_scores.save(HIGH_SCORES_PATH) {
+                        status = Some((format!("Meilleurs scores non enregistrés : {}", e), get_time()));
+                    }
+                    initials = None;
+                }
+            }
+            else if is_key_pressed(KeyCode::R) {
+                world = new_world(&playback);
+                recording = new_recording(&world);
+                autopilot = new_autopilot(&world);
+            }
+        }
+        else if let Some(open) = menu.as_mut() {
+            draw(&world,&particles,timestep.alpha(),&background_texture).await;
+            draw_controls_menu(open,&keyboard.controls,world.screen_size());
+            if edit_controls(open,&mut keyboard.controls) {
+                menu = None;
+                let message = match keyboard.controls.save(PLAYER_CONTROLS_PATH) {
+                    Ok(()) => format!("Commandes enregistrées dans {}", PLAYER_CONTROLS_PATH),
+                    Err(e) => format!("Commandes non enregistrées : {}", e),
+                };
+                status = Some((message, get_time()));
+            }
+        }
+        else if paused {
+            draw(&world,&particles,timestep.alpha(),&background_texture).await;
+            draw_text("PAUSE", world.screen_size().0 / 2.0 - 60.0, world.screen_size().1 / 2.0, 50.0, WHITE);
+            if action_pressed(&keyboard.controls, Action::Pause) {
+                paused = false;
+            }
+            if action_down(&keyboard.controls, Action::Quit) {
+                save_recording(&options.record, &recording);
+                break;
             }
         }
         else {
-            draw(&vec_asteroids,&spaceship,&missiles,&background_texture,&asteroid_texture).await;
+            if action_down(&keyboard.controls, Action::Quit) {
+                save_recording(&options.record, &recording);
+                break;
+            } 
+            if action_pressed(&keyboard.controls, Action::Pause) {
+                paused = true;
+            }
+            // Le menu des commandes s'ouvre sur la première action
+            if is_key_pressed(KeyCode::F1) {
+                menu = Some(ControlsMenu { selected: 0, capture: None });
+            }
+            // Sauvegarde rapide de la partie en cours
+            if is_key_pressed(KeyCode::F5) {
+                let message = match save_game(&world, QUICKSAVE_PATH) {
+                    Ok(()) => "Partie sauvegardée".to_string(),
+                    Err(e) => format!("Sauvegarde impossible : {}", e),
+                };
+                status = Some((message, get_time()));
+            }
 
-            show_elapsed_time(Instant::now()-start_time);
+            keyboard.poll();
+            for _ in 0..timestep.advance(get_frame_time()) {
+                // Les commandes viennent du replay s'il y en a un, du pilote automatique
+                // en mode démonstration, du clavier sinon
+                let source: &mut dyn InputSource = match (playback.as_mut(), autopilot.as_mut()) {
+                    (Some(replay), _) => replay,
+                    (None, Some(autopilot)) => autopilot,
+                    (None, None) => &mut keyboard,
+                };
+                // Le replay est terminé : la partie reste figée
+                let Some(input) = source.next_input(&world) else { break };
+                if let Some(recording) = recording.as_mut() {
+                    recording.record(input);
+                }
+                // Le vaisseau touché est replacé au centre : garder l'endroit du choc pour les débris
+                let ship_position = world.spaceship().get_position();
+                let ship_orientation = world.spaceship().get_orientation();
+                for event in world.step(&input) {
+                    match event {
+                        GameEvent::MissileFired => play_sounds(&missile_sound),
+                        GameEvent::AsteroidHit { position, size, .. } | GameEvent::AsteroidChipped { position, size } => {
+                            play_sounds(&collision_missile_sound);
+                            particles.burst(position, (size / 2.0) as usize, LIGHTGRAY);
+                        }
+                        GameEvent::ShipHit => {
+                            play_sounds(&collision_ship_sound);
+                            particles.burst(ship_position, 20, ORANGE);
+                        },
+                        GameEvent::GameOver => {
+                            play_sounds(&game_over_sound);
+                            particles.break_up(ship_position, ship_orientation);
+                            // Une partie rejouée ou jouée par le pilote automatique n'entre pas dans le tableau
+                            if playback.is_none() && autopilot.is_none() && high_scores.qualifies(world.score()) {
+                                initials = Some(String::new());
+                                // Ignore les touches tapées pendant la partie
+                                while get_char_pressed().is_some() {}
+                            }
+                        },
+                        GameEvent::SaucerFired => play_sounds(&missile_sound),
+                        GameEvent::SaucerDestroyed { position, .. } => {
+                            play_sounds(&collision_missile_sound);
+                            particles.burst(position, 25, GREEN);
+                        },
+                        GameEvent::PowerUpCollected { .. } => play_sounds(&missile_sound),
+                        GameEvent::SaucerAppeared { .. }
+                        | GameEvent::HyperspaceEntered
+                        | GameEvent::HyperspaceExited { .. }
+                        | GameEvent::ShipRespawned | GameEvent::WaveCleared { .. } | GameEvent::WaveStarted { .. } => {},
+                    }
+                }
+                // La flamme du réacteur remplace l'ancien vaisseau orange
+                if input.thrust && world.spaceship().is_visible() && !world.is_game_over() {
+                    particles.thrust(world.spaceship());
+                }
+                particles.update(GameWorld::TICK, world.screen_size());
+                if world.is_game_over() {
+                    save_recording(&options.record, &recording);
+                    break;
+                }
+            }
 
-            if handle_input(&mut spaceship,&mut missiles,&missile_sound) { break; } 
-    
-            update_model(&mut vec_asteroids,&mut spaceship,&mut missiles,&collision_missile_sound,
-                &collision_ship_sound,);
-    
-            if spaceship.is_destroyed() {
-                is_game_over = true;
-                play_sounds(&game_over_sound);
+            draw(&world,&particles,timestep.alpha(),&background_texture).await;
+            show_elapsed_time(world.elapsed());
+            // En bas de l'écran, au-dessus des messages d'état, pour ne pas chevaucher les bonus actifs
+            if let Some(autopilot) = &autopilot {
+                let y = world.screen_size().1 - 45.0;
+                draw_text(format!("DEMO - pilote automatique ({})", autopilot.skill().name()), 10.0, y, 20.0, GRAY);
             }
-            if vec_asteroids.is_empty() && !spaceship.is_destroyed() {
-                win=true;
+        }
+        if let Some((message, since)) = &status {
+            if get_time() - since < 2.0 {
+                draw_status(message,world.screen_size());
             }
         }
 
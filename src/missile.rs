@@ -1,41 +1,77 @@
 use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::config::MissileConfig;
+use crate::polygon;
+use crate::torus;
+use crate::world::GameWorld;
 use crate::stellarobject::StellarObject;
 
 
 /// Représente un missile tiré par le vaisseau dans le jeu.
+#[derive(Serialize, Deserialize)]
 pub struct Missile {
     position: Vec2,  // Position actuelle du missile.
+    previous_position: Vec2, // Position au pas de simulation précédent.
     speed: Vec2,     // Direction et vitesse du missile.
     is_active: bool,// Indique si le missile est actif ou non.
-    screen_size:(f32,f32) ,// Taille de la fenetre (width,height)
+    piercing: bool, // Un missile perçant traverse les astéroïdes touchés.
+    lifetime: f32,  // Temps restant avant que le missile ne disparaisse.
 }
 
 impl Missile {
-    /// Vitesse constante des missiles en unités par seconde.
-    pub const SPEED: f32 = 80.0;
+    /// Vitesse par défaut des missiles en unités par seconde (voir [`MissileConfig`]).
+    pub const SPEED: f32 = 600.0;
+    /// Durée de vie par défaut des missiles en secondes.
+    pub const LIFETIME: f32 = 1.0;
 
     /// Crée un nouveau missile avec la position initiale et l'orientation donnée.
     ///
     /// # Arguments
     /// * `position` - Position de départ du missile.
     /// * `orientation` - Angle en radians qui détermine la direction du missile.
-    /// * `screen_size` - taille de la fenetre de jeu.
-    pub fn new(position: Vec2, orientation: f32,screen_size:(f32,f32)) -> Self {
+    pub fn new(position: Vec2, orientation: f32) -> Self {
+        Self::with_config(position, orientation, MissileConfig::default())
+    }
+
+    /// Crée un nouveau missile avec des réglages donnés.
+    ///
+    /// # Arguments
+    /// * `position` - Position de départ du missile.
+    /// * `orientation` - Angle en radians qui détermine la direction du missile.
+    /// * `config` - les réglages des missiles.
+    pub fn with_config(position: Vec2, orientation: f32,config: MissileConfig) -> Self {
         // Calcule la vitesse en fonction de l'angle d'orientation.
         let speed = vec2(
-            orientation.sin() * Self::SPEED,
-            -orientation.cos() * Self::SPEED,
-            
+            orientation.sin() * config.speed,
+            -orientation.cos() * config.speed,
         );
         Self {
             position,
+            previous_position: position,
             speed,
             is_active: true,
-            screen_size,
+            piercing: false,
+            lifetime: config.lifetime,
         }
     }
 
-    /// Vérifie si le missile est toujours actif (c'est-à-dire à l'écran).
+    /// Retourne la vitesse du missile.
+    ///
+    /// # Returns
+    /// * La vitesse en unités par seconde.
+    pub fn get_speed(&self) -> Vec2 {
+        self.speed
+    }
+
+    /// Ajoute une vitesse à celle du missile, par exemple celle du vaisseau qui le tire.
+    ///
+    /// # Arguments
+    /// * `velocity` - La vitesse à ajouter, en unités par seconde.
+    pub fn add_velocity(&mut self, velocity: Vec2) {
+        self.speed += velocity;
+    }
+
+    /// Vérifie si le missile est toujours actif (ni expiré, ni arrêté par une cible).
     ///
     /// # Returns
     /// * `true` si le missile est actif, `false` sinon.
@@ -43,6 +79,19 @@ impl Missile {
         self.is_active
     }
 
+    /// Rend le missile perçant : il traverse les astéroïdes qu'il touche au lieu de disparaître.
+    ///
+    /// # Arguments
+    /// * `piercing` - `true` pour un missile perçant.
+    pub fn set_piercing(&mut self, piercing: bool) {
+        self.piercing = piercing;
+    }
+
+    /// Désactive le missile, après avoir touché sa cible.
+    pub fn deactivate(&mut self) {
+        self.is_active = false;
+    }
+
 }
 
 /// Implémentation du trait StellarObject pour la struct Missile
@@ -55,27 +104,39 @@ impl StellarObject for Missile {
         self.position
     }
 
+    /// Renvoie la position du missile au pas précédent.
+    fn get_previous_position(&self) -> Vec2 {
+        self.previous_position
+    }
+
     /// Met à jour la position du missile en tenant compte de sa vitesse.
     ///
-    /// La méthode désactive le missile s'il sort des limites de l'écran.
-    fn update(&mut self) {
+    /// Le missile réapparaît de l'autre côté s'il sort de l'écran, et se désactive
+    /// à la fin de sa durée de vie.
+    ///
+    /// # Arguments
+    /// * `dt` - La durée du pas de simulation en secondes.
+    fn update(&mut self, dt: f32) {
         if self.is_active {
             // Mise à jour de la position .
-            
-            self.position += self.speed * 0.04;
-
-            // Désactivation si le missile quitte l'écran.
-            if self.position.x < 0.0
-                || self.position.x > self.screen_size.0
-                || self.position.y < 0.0
-                || self.position.y > self.screen_size.1
-            {
+            self.previous_position = self.position;
+            self.position += self.speed * dt;
+            let moved = self.position;
+            self.position = torus::wrap(self.position, GameWorld::SIZE);
+            // Décale aussi la position précédente pour que l'interpolation ne traverse pas l'écran
+            self.previous_position += self.position - moved;
+
+            // Désactivation à la fin de la durée de vie.
+            self.lifetime -= dt;
+            if self.lifetime <= 0.0 {
                 self.is_active = false;
             }
         }
     }
 
-    /// Vérifie si le missile a heurté un astéroïde.
+    /// Vérifie si le missile a heurté un astéroïde : le missile doit être à l'intérieur
+    /// de son contour, ou de son cercle s'il n'a pas de contour.
+    /// Le missile disparaît, sauf s'il est perçant.
     ///
     /// # Arguments
     /// * `_other` - Une réference a un dyn StelarObject.
@@ -84,9 +145,16 @@ impl StellarObject for Missile {
     /// * `true` si une collision est détectée, `false` sinon.
     fn check_collision(&mut self, _other:&dyn StellarObject) -> bool {
         if self.is_active {
-            let distance = self.position.distance(_other.get_position());
-            if distance < _other.get_size(){
-                self.is_active=false;
+            // Le missile est vu du côté du bord le plus proche de sa cible
+            let outline = _other.get_outline();
+            let position = torus::nearest_image(self.position, _other.get_position(), GameWorld::SIZE);
+            let hit = if outline.is_empty() {
+                position.distance(_other.get_position()) < _other.get_size()
+            } else {
+                polygon::contains_point(&outline, position)
+            };
+            if hit {
+                self.is_active=self.piercing;
                 return true; 
             }else{
                 return false;
@@ -98,17 +166,19 @@ impl StellarObject for Missile {
 
 /// Testes unitaires 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)] // Les tests d'origine comparent les booléens avec `assert_eq!`
 mod tests {
     use super::*;
+    use ::rand::{rngs::SmallRng, SeedableRng};
+    const DT: f32 = 1.0 / 60.0;
     #[test]
     fn test_missile_update() {
         let position = vec2(100.0, 100.0);
         let orientation = 0.0; 
-        let screen_size = (800.0, 600.0);
-        let mut missile = Missile::new(position, orientation, screen_size);
+        let mut missile = Missile::new(position, orientation);
 
         // Mise à jour de la position
-        missile.update();
+        missile.update(DT);
         
         // Vérifie que la position a changé
         assert_ne!(missile.get_position(), position);
@@ -117,26 +187,49 @@ mod tests {
     fn test_missile_out_of_bounds() {
         let position = vec2(100.0, 100.0);
         let orientation = 0.0;
-        let screen_size = (800.0, 600.0);
-        let mut missile = Missile::new(position, orientation, screen_size);
+        let mut missile = Missile::new(position, orientation);
 
-        // Simule plusieurs mises à jour(position) pour faire sortir le missile de l'écran
+        // Simule plusieurs mises à jour(position), au-delà de la durée de vie du missile
         for _ in 0..100 {
-            missile.update();
+            missile.update(DT);
         }
 
         // Le missile devrait être désactivé
         assert_eq!(missile.is_active(), false);
     }
+    #[test]
+    fn test_missile_wraps_until_expired() {
+        let config = MissileConfig { lifetime: 0.5, ..MissileConfig::default() };
+        // Missile près du bord haut, dirigé vers le haut
+        let mut missile = Missile::with_config(vec2(100.0, 5.0), 0.0, config);
+
+        missile.update(DT);
+        assert!(missile.is_active());
+        assert!(missile.get_position().y > 590.0);
+
+        for _ in 0..29 {
+            missile.update(DT);
+        }
+        assert!(!missile.is_active());
+    }
+
+    #[test]
+    fn test_missile_inherits_velocity() {
+        let mut missile = Missile::new(vec2(100.0, 100.0), 0.0);
+        missile.add_velocity(vec2(60.0, 0.0));
+
+        missile.update(DT);
+
+        assert_eq!(missile.get_position(), vec2(101.0, 90.0));
+    }
      
      #[test]
     fn test_missile_collision() {
          let position = vec2(100.0, 100.0);
          let orientation = 0.0; 
-         let screen_size = (800.0, 600.0);
-         let mut missile = Missile::new(position, orientation, screen_size);
+         let mut missile = Missile::new(position, orientation);
  
-         let mut asteroid=crate::Asteroid::new(10.0, screen_size);
+         let mut asteroid=crate::Asteroid::new(10.0, &mut SmallRng::seed_from_u64(0));
          asteroid.set_position(vec2(105.0, 105.0));
 
  
@@ -149,10 +242,9 @@ mod tests {
      fn test_no_collision() {
         let position = vec2(100.0, 100.0);
         let orientation = 0.0; // Missile dirigé vers le haut
-        let screen_size = (800.0, 600.0);
-        let mut missile = Missile::new(position, orientation, screen_size);
+        let mut missile = Missile::new(position, orientation);
 
-        let mut asteroid=crate::Asteroid::new(10.0, screen_size);
+        let mut asteroid=crate::Asteroid::new(10.0, &mut SmallRng::seed_from_u64(0));
         asteroid.set_position(vec2(120.0, 120.0));
 
         // Vérifie qu'il n'y a pas de collision
@@ -160,5 +252,28 @@ mod tests {
         // Vérifie que le missile est toujours actif
         assert_eq!(missile.is_active(), true);
     }
+    #[test]
+    fn test_missile_collision_across_edge() {
+        let mut missile = Missile::new(vec2(15.0, 300.0), 0.0);
+
+        // Astéroïde à cheval sur le bord droit : le missile le touche de l'autre côté
+        let mut asteroid=crate::Asteroid::new(50.0, &mut SmallRng::seed_from_u64(0));
+        asteroid.set_position(vec2(790.0, 300.0));
+
+        assert!(missile.check_collision(&asteroid));
+    }
+
+    #[test]
+    fn test_piercing_collision() {
+        let mut missile = Missile::new(vec2(100.0, 100.0), 0.0);
+        missile.set_piercing(true);
+
+        let mut asteroid=crate::Asteroid::new(10.0, &mut SmallRng::seed_from_u64(0));
+        asteroid.set_position(vec2(105.0, 105.0));
+
+        // Le missile perçant touche l'astéroïde et continue sa course
+        assert!(missile.check_collision(&asteroid));
+        assert!(missile.is_active());
+    }
 
 }
@@ -0,0 +1,268 @@
+//! Particules décoratives : débris des explosions, flamme du réacteur et éclatement
+//! du vaisseau.
+//!
+//! Les particules ne font pas partie de la simulation : elles ne touchent rien, ne sont
+//! ni sauvegardées ni rejouées, et l'interface les crée à partir des [`GameEvent`](crate::GameEvent).
+//! Elles vivent dans un tableau de taille fixe alloué une fois pour toutes : quand il est
+//! plein, une nouvelle particule remplace la plus ancienne.
+use std::f32::consts::TAU;
+
+use macroquad::prelude::*;
+use ::rand::{rngs::SmallRng, Rng, SeedableRng};
+use crate::polygon;
+use crate::spaceship::Spaceship;
+use crate::stellarobject::StellarObject;
+use crate::torus;
+
+/// Une particule : un point, ou un segment qui tourne sur lui-même.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Particle {
+    /// Position du centre de la particule.
+    pub position: Vec2,
+    /// Vitesse en unités par seconde.
+    pub velocity: Vec2,
+    /// Temps restant avant que la particule ne disparaisse, en secondes.
+    pub life: f32,
+    /// Durée de vie totale, en secondes.
+    pub lifetime: f32,
+    /// Couleur de la particule à sa création.
+    pub color: Color,
+    /// Rayon d'un point, ou demi-longueur d'un segment.
+    pub size: f32,
+    /// Angle d'un segment en radians, `None` pour un point.
+    pub angle: Option<f32>,
+    /// Vitesse de rotation d'un segment en radians par seconde.
+    pub spin: f32,
+}
+
+impl Particle {
+    /// Une particule éteinte, qui occupe une place libre du tableau.
+    const DEAD: Particle = Particle {
+        position: Vec2::ZERO,
+        velocity: Vec2::ZERO,
+        life: 0.0,
+        lifetime: 1.0,
+        color: WHITE,
+        size: 0.0,
+        angle: None,
+        spin: 0.0,
+    };
+
+    /// Indique si la particule est encore visible.
+    pub fn is_alive(&self) -> bool {
+        self.life > 0.0
+    }
+
+    /// Retourne la couleur de la particule, de plus en plus transparente
+    /// à mesure qu'elle vieillit.
+    pub fn faded_color(&self) -> Color {
+        let mut color = self.color;
+        color.a *= (self.life / self.lifetime).clamp(0.0, 1.0);
+        color
+    }
+}
+
+/// Ensemble des particules à l'écran.
+pub struct ParticleSystem {
+    particles: Vec<Particle>, // Tableau de taille fixe, les particules éteintes y restent
+    next: usize,              // Prochaine place utilisée, la plus ancienne quand tout est plein
+    rng: SmallRng,            // Hasard propre aux particules, hors de la simulation
+}
+
+impl ParticleSystem {
+    /// Nombre de particules par défaut.
+    pub const CAPACITY: usize = 2048;
+    /// Durée de vie des débris d'une explosion, en secondes.
+    pub const DEBRIS_LIFETIME: f32 = 0.8;
+    /// Vitesse maximale des débris d'une explosion, en unités par seconde.
+    pub const DEBRIS_SPEED: f32 = 120.0;
+    /// Durée de vie d'une particule de la flamme du réacteur, en secondes.
+    pub const FLAME_LIFETIME: f32 = 0.3;
+    /// Vitesse d'éjection de la flamme du réacteur, en unités par seconde.
+    pub const FLAME_SPEED: f32 = 150.0;
+    /// Durée de l'éclatement du vaisseau en fin de partie, en secondes.
+    pub const BREAK_UP_LIFETIME: f32 = 2.0;
+
+    /// Crée un ensemble de particules vide.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Le nombre maximal de particules visibles en même temps.
+    /// * `seed` - La graine du hasard des particules.
+    pub fn new(capacity: usize, seed: u64) -> Self {
+        Self {
+            particles: vec![Particle::DEAD; capacity.max(1)],
+            next: 0,
+            rng: SmallRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Ajoute une particule, à la place de la plus ancienne si le tableau est plein.
+    ///
+    /// # Arguments
+    ///
+    /// * `particle` - La particule à ajouter.
+    pub fn emit(&mut self, particle: Particle) {
+        self.particles[self.next] = particle;
+        self.next = (self.next + 1) % self.particles.len();
+    }
+
+    /// Projette des débris dans toutes les directions, pour une explosion.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - Le centre de l'explosion.
+    /// * `count` - Le nombre de débris.
+    /// * `color` - La couleur des débris.
+    pub fn burst(&mut self, position: Vec2, count: usize, color: Color) {
+        for _ in 0..count {
+            let velocity = Vec2::from_angle(self.rng.gen_range(0.0..TAU))
+                * self.rng.gen_range(0.2..=1.0)
+                * Self::DEBRIS_SPEED;
+            let lifetime = Self::DEBRIS_LIFETIME * self.rng.gen_range(0.5..=1.0);
+            let size = self.rng.gen_range(1.0..=2.5);
+            self.emit(Particle {
+                position,
+                velocity,
+                life: lifetime,
+                lifetime,
+                color,
+                size,
+                ..Particle::DEAD
+            });
+        }
+    }
+
+    /// Crache une particule de flamme à l'arrière du vaisseau qui accélère.
+    ///
+    /// # Arguments
+    ///
+    /// * `spaceship` - Le vaisseau.
+    pub fn thrust(&mut self, spaceship: &Spaceship) {
+        let orientation = spaceship.get_orientation();
+        // Milieu de la base du triangle, et direction opposée au nez du vaisseau
+        let rear = (Spaceship::SHAPE[1] + Spaceship::SHAPE[2]) / 2.0;
+        let position = spaceship.get_position() + Vec2::from_angle(orientation).rotate(rear);
+        let backward = Vec2::from_angle(orientation).rotate(vec2(0.0, 1.0));
+        let jitter = self.rng.gen_range(-0.3..=0.3);
+        let velocity = spaceship.get_speed()
+            + Vec2::from_angle(jitter).rotate(backward) * Self::FLAME_SPEED * self.rng.gen_range(0.6..=1.0);
+        let color = if self.rng.gen_bool(0.5) { ORANGE } else { YELLOW };
+        self.emit(Particle {
+            position,
+            velocity,
+            life: Self::FLAME_LIFETIME,
+            lifetime: Self::FLAME_LIFETIME,
+            color,
+            size: 2.0,
+            ..Particle::DEAD
+        });
+    }
+
+    /// Fait éclater le vaisseau : les trois côtés de son triangle s'éloignent
+    /// en tournoyant, au milieu d'un nuage de débris.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - La position du vaisseau.
+    /// * `orientation` - L'orientation du vaisseau en radians.
+    pub fn break_up(&mut self, position: Vec2, orientation: f32) {
+        let outline = polygon::transform(&Spaceship::SHAPE, position, orientation);
+        for (i, &a) in outline.iter().enumerate() {
+            let b = outline[(i + 1) % outline.len()];
+            let middle = (a + b) / 2.0;
+            let velocity = (middle - position).normalize_or_zero() * self.rng.gen_range(20.0..=40.0);
+            let spin = self.rng.gen_range(-3.0..=3.0);
+            self.emit(Particle {
+                position: middle,
+                velocity,
+                life: Self::BREAK_UP_LIFETIME,
+                lifetime: Self::BREAK_UP_LIFETIME,
+                color: WHITE,
+                size: a.distance(b) / 2.0,
+                angle: Some((b - a).to_angle()),
+                spin,
+            });
+        }
+        self.burst(position, 30, WHITE);
+    }
+
+    /// Déplace les particules, fait tourner les segments et décompte leur durée de vie.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - La durée écoulée en secondes.
+    /// * `screen_size` - La taille du monde de jeu : les particules en font le tour comme le reste.
+    pub fn update(&mut self, dt: f32, screen_size: (f32, f32)) {
+        for particle in self.particles.iter_mut().filter(|p| p.is_alive()) {
+            particle.life -= dt;
+            particle.position = torus::wrap(particle.position + particle.velocity * dt, screen_size);
+            if let Some(angle) = particle.angle.as_mut() {
+                *angle += particle.spin * dt;
+            }
+        }
+    }
+
+    /// Parcourt les particules visibles.
+    pub fn iter(&self) -> impl Iterator<Item = &Particle> {
+        self.particles.iter().filter(|p| p.is_alive())
+    }
+
+    /// Indique s'il ne reste aucune particule visible.
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Éteint toutes les particules, pour une nouvelle partie.
+    pub fn clear(&mut self) {
+        self.particles.fill(Particle::DEAD);
+    }
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: (f32, f32) = (800.0, 600.0);
+
+    #[test]
+    fn test_pool_replaces_oldest() {
+        let mut particles = ParticleSystem::new(10, 0);
+
+        particles.burst(vec2(100.0, 100.0), 8, WHITE);
+        particles.burst(vec2(500.0, 500.0), 8, RED);
+
+        // Le tableau ne grandit pas : les plus anciens débris ont été remplacés
+        assert_eq!(particles.iter().count(), 10);
+        assert_eq!(particles.iter().filter(|p| p.color == RED).count(), 8);
+    }
+
+    #[test]
+    fn test_particles_fade_and_die() {
+        let mut particles = ParticleSystem::new(ParticleSystem::CAPACITY, 0);
+        particles.break_up(vec2(400.0, 300.0), 0.0);
+        let segments = particles.iter().filter(|p| p.angle.is_some()).count();
+        assert_eq!(segments, 3);
+
+        particles.update(ParticleSystem::BREAK_UP_LIFETIME / 2.0, SCREEN);
+        let segment = particles.iter().find(|p| p.angle.is_some()).unwrap();
+        assert!((segment.faded_color().a - 0.5).abs() < 1e-3);
+
+        particles.update(ParticleSystem::BREAK_UP_LIFETIME, SCREEN);
+        assert!(particles.is_empty());
+    }
+
+    #[test]
+    fn test_flame_leaves_from_rear() {
+        let mut particles = ParticleSystem::new(16, 0);
+        let spaceship = Spaceship::new();
+
+        particles.thrust(&spaceship);
+
+        // Le vaisseau pointe vers le haut : la flamme part vers le bas
+        let flame = particles.iter().next().unwrap();
+        assert!(flame.position.y > spaceship.get_position().y);
+        assert!(flame.velocity.y > 0.0);
+    }
+}
@@ -0,0 +1,163 @@
+//! Géométrie des contours polygonaux, pour des collisions fidèles à ce qui est dessiné.
+//!
+//! Les contours sont des polygones simples étoilés par rapport à leur centre
+//! (la moyenne de leurs sommets) : c'est le cas des polygones convexes comme le vaisseau
+//! et des astéroïdes irréguliers. Un tel polygone se découpe en triangles autour de
+//! son centre, et deux polygones se touchent si deux de leurs triangles se touchent,
+//! ce que le théorème de l'axe séparateur (SAT) teste sur des triangles convexes.
+use macroquad::prelude::*;
+
+/// Place un contour exprimé autour de l'origine à une position et une rotation données.
+///
+/// # Arguments
+///
+/// * `shape` - Les sommets du contour autour de l'origine.
+/// * `position` - La position du centre du contour.
+/// * `rotation` - La rotation du contour en radians.
+///
+/// # Returns
+///
+/// Les sommets du contour à l'écran.
+pub fn transform(shape: &[Vec2], position: Vec2, rotation: f32) -> Vec<Vec2> {
+    let rotation = Vec2::from_angle(rotation);
+    shape.iter().map(|&point| position + rotation.rotate(point)).collect()
+}
+
+/// Indique si un point est à l'intérieur d'un polygone simple (règle pair-impair).
+///
+/// # Arguments
+///
+/// * `polygon` - Les sommets du polygone.
+/// * `point` - Le point à tester.
+pub fn contains_point(polygon: &[Vec2], point: Vec2) -> bool {
+    let mut inside = false;
+    for (a, b) in edges(polygon) {
+        // L'arête traverse l'horizontale du point, à sa droite
+        if (a.y > point.y) != (b.y > point.y) && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Indique si deux polygones étoilés se chevauchent.
+///
+/// # Arguments
+///
+/// * `a` - Les sommets du premier polygone.
+/// * `b` - Les sommets du second polygone.
+pub fn polygons_intersect(a: &[Vec2], b: &[Vec2]) -> bool {
+    let (fan_a, fan_b) = (fan(a), fan(b));
+    fan_a.iter().any(|ta| fan_b.iter().any(|tb| triangles_intersect(ta, tb)))
+}
+
+/// Indique si un cercle chevauche un polygone simple.
+///
+/// # Arguments
+///
+/// * `center` - Le centre du cercle.
+/// * `radius` - Le rayon du cercle (0 pour un point).
+/// * `polygon` - Les sommets du polygone.
+pub fn circle_intersects_polygon(center: Vec2, radius: f32, polygon: &[Vec2]) -> bool {
+    contains_point(polygon, center)
+        || edges(polygon).any(|(a, b)| distance_to_segment(center, a, b) < radius)
+}
+
+/// Parcourt les arêtes d'un polygone, la dernière refermant le contour.
+fn edges(polygon: &[Vec2]) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+    polygon.iter().zip(polygon.iter().cycle().skip(1)).map(|(&a, &b)| (a, b))
+}
+
+/// Découpe un polygone étoilé en triangles autour de son centre.
+fn fan(polygon: &[Vec2]) -> Vec<[Vec2; 3]> {
+    if let [a, b, c] = *polygon {
+        return vec![[a, b, c]];
+    }
+    let center = polygon.iter().copied().sum::<Vec2>() / polygon.len() as f32;
+    edges(polygon).map(|(a, b)| [center, a, b]).collect()
+}
+
+/// Indique si deux triangles se chevauchent : aucune normale de leurs arêtes
+/// ne doit séparer leurs projections.
+fn triangles_intersect(a: &[Vec2; 3], b: &[Vec2; 3]) -> bool {
+    edges(a).chain(edges(b)).all(|(p, q)| {
+        let axis = (q - p).perp();
+        let (min_a, max_a) = project(a, axis);
+        let (min_b, max_b) = project(b, axis);
+        min_a <= max_b && min_b <= max_a
+    })
+}
+
+/// Projette les sommets d'un polygone sur un axe.
+fn project(polygon: &[Vec2], axis: Vec2) -> (f32, f32) {
+    polygon.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), point| {
+        let d = point.dot(axis);
+        (min.min(d), max.max(d))
+    })
+}
+
+/// Distance d'un point à un segment.
+fn distance_to_segment(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let t = if ab.length_squared() > 0.0 { ((point - a).dot(ab) / ab.length_squared()).clamp(0.0, 1.0) } else { 0.0 };
+    point.distance(a + ab * t)
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Une étoile à cinq branches autour de l'origine, étoilée mais pas convexe,
+    /// avec une branche vers la droite.
+    fn star() -> Vec<Vec2> {
+        (0..10)
+            .map(|i| Vec2::from_angle(i as f32 * std::f32::consts::PI / 5.0) * if i % 2 == 0 { 2.0 } else { 0.8 })
+            .collect()
+    }
+
+    /// Direction entre deux branches de l'étoile.
+    fn between_branches() -> Vec2 {
+        Vec2::from_angle(std::f32::consts::PI / 5.0)
+    }
+
+    #[test]
+    fn test_contains_point() {
+        let polygon = star();
+
+        assert!(contains_point(&polygon, vec2(1.8, 0.0)));
+        assert!(contains_point(&polygon, vec2(0.0, 0.0)));
+        // Entre deux branches, dans le cercle englobant
+        assert!(!contains_point(&polygon, between_branches() * 1.5));
+        assert!(!contains_point(&polygon, vec2(2.5, 0.0)));
+    }
+
+    #[test]
+    fn test_polygons_intersect() {
+        let polygon = star();
+        let triangle = |offset: Vec2| transform(&[vec2(0.0, -0.2), vec2(0.2, 0.2), vec2(-0.2, 0.2)], offset, 0.0);
+
+        assert!(polygons_intersect(&polygon, &triangle(vec2(1.9, 0.0))));
+        assert!(polygons_intersect(&triangle(vec2(0.0, 0.0)), &polygon));
+        // Entre deux branches, même si les cercles englobants se chevauchent
+        assert!(!polygons_intersect(&polygon, &triangle(between_branches() * 1.6)));
+    }
+
+    #[test]
+    fn test_circle_intersects_polygon() {
+        let polygon = star();
+
+        assert!(circle_intersects_polygon(vec2(2.5, 0.0), 0.6, &polygon));
+        assert!(!circle_intersects_polygon(vec2(2.5, 0.0), 0.4, &polygon));
+        assert!(circle_intersects_polygon(vec2(0.5, 0.0), 0.0, &polygon));
+    }
+
+    #[test]
+    fn test_transform() {
+        let shape = [vec2(1.0, 0.0)];
+
+        let moved = transform(&shape, vec2(10.0, 10.0), std::f32::consts::FRAC_PI_2);
+
+        assert!(moved[0].abs_diff_eq(vec2(10.0, 11.0), 1e-5));
+    }
+}
@@ -0,0 +1,231 @@
+use std::f32::consts::TAU;
+
+use macroquad::prelude::*;
+use ::rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::config::PowerUpConfig;
+use crate::stellarobject::StellarObject;
+use crate::torus;
+use crate::world::GameWorld;
+
+/// Les bonus que peut lâcher un astéroïde détruit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PowerUpKind {
+    /// Tire trois missiles en éventail.
+    MultiShot,
+    /// Tir automatique tant que le bouton de tir est enfoncé.
+    RapidFire,
+    /// Rend un niveau de bouclier, dans la limite de `powerup.max_shield`.
+    ShieldRecharge,
+    /// Les missiles traversent les astéroïdes au lieu de disparaître.
+    Piercing,
+}
+
+impl PowerUpKind {
+    /// Tous les bonus, dans l'ordre du tirage aléatoire.
+    pub const ALL: [PowerUpKind; 4] = [
+        PowerUpKind::MultiShot,
+        PowerUpKind::RapidFire,
+        PowerUpKind::ShieldRecharge,
+        PowerUpKind::Piercing,
+    ];
+}
+
+/// Représente un bonus à ramasser : il dérive, réapparaît de l'autre côté
+/// de l'écran et disparaît au bout de `powerup.lifetime` secondes.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PowerUp {
+    kind: PowerUpKind,
+    position: Vec2,          // Position actuelle du bonus
+    previous_position: Vec2, // Position au pas de simulation précédent
+    speed: Vec2,             // Vitesse de dérive (unités par seconde)
+    lifetime: f32,           // Temps restant avant sa disparition
+    config: PowerUpConfig,
+}
+
+impl PowerUp {
+    /// Crée un bonus à la position donnée, dérivant dans une direction aléatoire.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Le type de bonus.
+    /// * `position` - La position de l'astéroïde qui l'a lâché.
+    /// * `config` - Les réglages des bonus.
+    /// * `rng` - Le générateur aléatoire de la partie.
+    pub fn with_config(
+        kind: PowerUpKind,
+        position: Vec2,
+        config: PowerUpConfig,
+        rng: &mut impl Rng,
+    ) -> Self {
+        Self {
+            kind,
+            position,
+            previous_position: position,
+            speed: Vec2::from_angle(rng.gen_range(0.0..TAU)) * config.speed,
+            lifetime: config.lifetime,
+            config,
+        }
+    }
+
+    /// Retourne le type du bonus.
+    pub fn kind(&self) -> PowerUpKind {
+        self.kind
+    }
+
+    /// Retourne le temps restant avant la disparition du bonus.
+    pub fn lifetime(&self) -> f32 {
+        self.lifetime
+    }
+
+    /// Indique si le bonus a disparu faute d'avoir été ramassé.
+    pub fn is_expired(&self) -> bool {
+        self.lifetime <= 0.0
+    }
+}
+
+/// Implémentation du trait StellarObject pour la struct PowerUp
+impl StellarObject for PowerUp {
+    /// Retourne la position actuelle du bonus.
+    fn get_position(&self) -> Vec2 {
+        self.position
+    }
+
+    /// Retourne la position du bonus au pas précédent.
+    fn get_previous_position(&self) -> Vec2 {
+        self.previous_position
+    }
+
+    /// Fait dériver le bonus, gère le retour de l'autre côté de l'écran
+    /// et décompte sa durée de vie.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - La durée du pas de simulation en secondes.
+    fn update(&mut self, dt: f32) {
+        self.lifetime -= dt;
+        self.previous_position = self.position;
+        self.position += self.speed * dt;
+        let moved = self.position;
+        self.position = torus::wrap(self.position, GameWorld::SIZE);
+        // Décale aussi la position précédente pour que l'interpolation ne traverse pas l'écran
+        self.previous_position += self.position - moved;
+    }
+
+    /// Vérifie si le vaisseau touche le bonus pour le ramasser.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Le vaisseau.
+    ///
+    /// # Returns
+    ///
+    /// * `true` si le bonus est ramassé, `false` sinon.
+    fn check_collision(&mut self, other: &dyn StellarObject) -> bool {
+        !self.is_expired()
+            && torus::distance(self.position, other.get_position(), GameWorld::SIZE) < self.config.size + other.get_size()
+    }
+
+    /// Retourne le rayon du bonus.
+    fn get_size(&self) -> f32 {
+        self.config.size
+    }
+}
+
+/// Durées restantes des bonus temporaires ramassés par le joueur.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ActiveEffects {
+    multi_shot: f32,
+    rapid_fire: f32,
+    piercing: f32,
+}
+
+impl ActiveEffects {
+    /// Retourne le temps restant d'un bonus, 0 s'il est inactif ou instantané.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Le type de bonus.
+    pub fn remaining(&self, kind: PowerUpKind) -> f32 {
+        match kind {
+            PowerUpKind::MultiShot => self.multi_shot,
+            PowerUpKind::RapidFire => self.rapid_fire,
+            PowerUpKind::Piercing => self.piercing,
+            PowerUpKind::ShieldRecharge => 0.0,
+        }
+    }
+
+    /// Indique si un bonus est actif.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Le type de bonus.
+    pub fn is_active(&self, kind: PowerUpKind) -> bool {
+        self.remaining(kind) > 0.0
+    }
+
+    /// Active un bonus temporaire pour une durée donnée ; ramasser un bonus
+    /// déjà actif repart de cette durée. Sans effet pour la recharge du bouclier.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Le type de bonus.
+    /// * `duration` - La durée de l'effet en secondes.
+    pub fn activate(&mut self, kind: PowerUpKind, duration: f32) {
+        match kind {
+            PowerUpKind::MultiShot => self.multi_shot = duration,
+            PowerUpKind::RapidFire => self.rapid_fire = duration,
+            PowerUpKind::Piercing => self.piercing = duration,
+            PowerUpKind::ShieldRecharge => {}
+        }
+    }
+
+    /// Décompte la durée des bonus actifs.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - La durée du pas de simulation en secondes.
+    pub fn update(&mut self, dt: f32) {
+        for timer in [&mut self.multi_shot, &mut self.rapid_fire, &mut self.piercing] {
+            *timer = (*timer - dt).max(0.0);
+        }
+    }
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::rand::{rngs::SmallRng, SeedableRng};
+
+    const DT: f32 = 1.0 / 60.0;
+
+    #[test]
+    fn test_pickup_wraps_and_expires() {
+        let config = PowerUpConfig::default();
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut power_up = PowerUp::with_config(PowerUpKind::Piercing, vec2(0.0, 0.0), config, &mut rng);
+
+        let ticks = (config.lifetime / DT).ceil() as usize;
+        for _ in 0..ticks {
+            power_up.update(DT);
+            let position = power_up.get_position();
+            assert!((0.0..800.0).contains(&position.x) && (0.0..600.0).contains(&position.y));
+        }
+        assert!(power_up.is_expired());
+    }
+
+    #[test]
+    fn test_effects_expire() {
+        let mut effects = ActiveEffects::default();
+        effects.activate(PowerUpKind::RapidFire, 1.0);
+        effects.activate(PowerUpKind::ShieldRecharge, 1.0);
+
+        assert!(effects.is_active(PowerUpKind::RapidFire));
+        assert!(!effects.is_active(PowerUpKind::ShieldRecharge));
+        effects.update(0.5);
+        assert_eq!(effects.remaining(PowerUpKind::RapidFire), 0.5);
+        effects.update(0.6);
+        assert!(!effects.is_active(PowerUpKind::RapidFire));
+    }
+}
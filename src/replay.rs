@@ -0,0 +1,291 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use crate::config::GameConfig;
+use crate::world::{GameWorld, PlayerInput};
+
+/// Enregistrement d'une partie : la graine, les réglages et les commandes du joueur
+/// à chaque pas de simulation.
+///
+/// La simulation étant déterministe, rejouer ces commandes sur un [`GameWorld`]
+/// créé par [`Replay::new_world`] reproduit exactement la partie.
+///
+/// Le fichier est un texte versionné :
+///
+/// ```text
+/// asteroid-replay 4
+/// seed 42
+/// screen 800 600
+/// config 2
+/// [asteroid]
+/// max_count = 8
+/// 120 00
+/// 3 11
+/// ```
+///
+/// La ligne `screen` rappelle la taille du monde de jeu : un enregistrement d'une autre
+/// taille que [`GameWorld::SIZE`] est refusé, car il ne se rejouerait pas à l'identique.
+/// La ligne `config` donne le nombre de lignes TOML de réglages qui la suivent.
+/// Les versions précédentes sont refusées, car leurs commandes n'ont plus le même sens
+/// et une ancienne partie ne se rejouerait plus à l'identique : en version 3, le tir se
+/// déclenche à l'appui et non plus à chaque pas où il est enfoncé ; la version 4 ajoute
+/// le saut en hyperespace.
+/// Chaque ligne suivante indique un nombre de pas consécutifs suivi des
+/// commandes communes à ces pas, encodées par [`PlayerInput::to_bits`] en hexadécimal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    seed: u64,
+    config: GameConfig,
+    inputs: Vec<PlayerInput>,
+}
+
+impl Replay {
+    /// Version actuelle du format de fichier.
+    pub const VERSION: u32 = 4;
+    /// Nombre maximal de pas d'un enregistrement (dix heures de jeu), pour qu'un fichier
+    /// mal formé ne fasse pas déborder la mémoire.
+    pub const MAX_TICKS: usize = 10 * 60 * 60 * 60;
+    /// Mot magique en tête de fichier.
+    const MAGIC: &'static str = "asteroid-replay";
+
+    /// Crée un enregistrement vide pour une partie.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - La graine de la partie enregistrée.
+    /// * `config` - Les réglages de la partie.
+    pub fn new(seed: u64, config: GameConfig) -> Self {
+        Self { seed, config, inputs: Vec::new() }
+    }
+
+    /// Retourne la graine de la partie enregistrée.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Retourne les réglages de la partie enregistrée.
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// Retourne les commandes enregistrées, une par pas de simulation.
+    pub fn inputs(&self) -> &[PlayerInput] {
+        &self.inputs
+    }
+
+    /// Ajoute les commandes d'un pas de simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Les commandes passées à [`GameWorld::step`].
+    pub fn record(&mut self, input: PlayerInput) {
+        self.inputs.push(input);
+    }
+
+    /// Crée la partie sur laquelle rejouer les commandes.
+    pub fn new_world(&self) -> GameWorld {
+        GameWorld::with_config(self.seed, self.config)
+    }
+
+    /// Ecrit l'enregistrement dans un fichier.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Le chemin du fichier à écrire.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+
+    /// Lit un enregistrement depuis un fichier.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Le chemin du fichier à lire.
+    ///
+    /// # Returns
+    ///
+    /// L'enregistrement lu, ou une erreur `InvalidData` si le fichier est mal formé
+    /// ou d'une version inconnue.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_text(&fs::read_to_string(path)?)
+    }
+
+    /// Sérialise l'enregistrement au format texte.
+    fn to_text(&self) -> String {
+        let config = self.config.to_toml();
+        let mut text = format!(
+            "{} {}\nseed {}\nscreen {} {}\nconfig {}\n{}",
+            Self::MAGIC, Self::VERSION, self.seed, GameWorld::SIZE.0, GameWorld::SIZE.1,
+            config.lines().count(), config
+        );
+        // Regroupe les pas consécutifs ayant les mêmes commandes
+        let mut inputs = self.inputs.iter().map(PlayerInput::to_bits).peekable();
+        while let Some(bits) = inputs.next() {
+            let mut count = 1;
+            while inputs.next_if_eq(&bits).is_some() {
+                count += 1;
+            }
+            text.push_str(&format!("{} {:02x}\n", count, bits));
+        }
+        text
+    }
+
+    /// Lit un enregistrement au format texte.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Le contenu du fichier.
+    fn from_text(text: &str) -> io::Result<Self> {
+        let mut lines = text.lines();
+        let mut header = |key: &str| -> io::Result<Vec<&str>> {
+            let line = lines.next().ok_or_else(|| invalid(format!("en-tête `{}` manquant", key)))?;
+            match line.split_whitespace().collect::<Vec<_>>().split_first() {
+                Some((k, values)) if *k == key => Ok(values.to_vec()),
+                _ => Err(invalid(format!("en-tête `{}` attendu, lu `{}`", key, line))),
+            }
+        };
+
+        let version = match header(Self::MAGIC)?[..] {
+            [version] => parse::<u32>(version)?,
+            _ => return Err(invalid("version attendue".to_string())),
+        };
+        if version != Self::VERSION {
+            return Err(invalid(format!("version de replay non supportée : {}", version)));
+        }
+        let seed = match header("seed")?[..] {
+            [seed] => parse(seed)?,
+            _ => return Err(invalid("graine attendue".to_string())),
+        };
+        let screen_size: (f32, f32) = match header("screen")?[..] {
+            [width, height] => (parse(width)?, parse(height)?),
+            _ => return Err(invalid("taille de fenetre attendue".to_string())),
+        };
+        if screen_size != GameWorld::SIZE {
+            return Err(invalid(format!("taille de monde non supportée : {} {}", screen_size.0, screen_size.1)));
+        }
+        let count = match header("config")?[..] {
+            [count] => parse(count)?,
+            _ => return Err(invalid("nombre de lignes de réglages attendu".to_string())),
+        };
+        let toml = lines.by_ref().take(count).collect::<Vec<_>>().join("\n");
+        let config = GameConfig::from_toml(&toml).map_err(|e| invalid(format!("réglages du replay : {}", e)))?;
+
+        let mut inputs = Vec::new();
+        for line in lines.filter(|l| !l.trim().is_empty()) {
+            let (count, bits) = line
+                .split_once(' ')
+                .ok_or_else(|| invalid(format!("ligne de commandes invalide : `{}`", line)))?;
+            let count: usize = parse(count)?;
+            let input = u8::from_str_radix(bits.trim(), 16).ok()
+                .and_then(PlayerInput::from_bits)
+                .ok_or_else(|| invalid(format!("commandes invalides : `{}`", bits)))?;
+            if inputs.len().checked_add(count).is_none_or(|total| total > Self::MAX_TICKS) {
+                return Err(invalid(format!("enregistrement de plus de {} pas", Self::MAX_TICKS)));
+            }
+            inputs.extend(std::iter::repeat_n(input, count));
+        }
+        Ok(Self { seed, config, inputs })
+    }
+}
+
+/// Construit une erreur de données invalides.
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Lit une valeur numérique d'un fichier de replay.
+fn parse<T: std::str::FromStr>(value: &str) -> io::Result<T> {
+    value.parse().map_err(|_| invalid(format!("valeur invalide : `{}`", value)))
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stellarobject::StellarObject;
+
+    #[test]
+    fn test_input_bits() {
+        for bits in 0..64 {
+            assert_eq!(PlayerInput::from_bits(bits).unwrap().to_bits(), bits);
+        }
+        assert_eq!(PlayerInput::from_bits(0x40), None);
+    }
+
+    #[test]
+    fn test_malformed_inputs() {
+        let header = format!("asteroid-replay {}\nseed 1\nscreen 800 600\nconfig 0\n", Replay::VERSION);
+
+        // Bit inconnu, puis nombre de pas démesuré
+        for inputs in ["1 40\n", "99999999999 00\n", "3000000 00\n"] {
+            let err = Replay::from_text(&(header.clone() + inputs)).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{}", inputs);
+        }
+    }
+
+    #[test]
+    fn test_text_roundtrip() {
+        let mut config = GameConfig::default();
+        config.ship.acceleration = 900.0;
+        let mut replay = Replay::new(42, config);
+        for i in 0..100 {
+            replay.record(PlayerInput { thrust: i < 50, fire: i % 10 == 0, ..Default::default() });
+        }
+
+        let text = replay.to_text();
+
+        assert!(text.starts_with(&format!("asteroid-replay {}\nseed 42\nscreen 800 600\nconfig ", Replay::VERSION)));
+        assert_eq!(Replay::from_text(&text).unwrap(), replay);
+    }
+
+    #[test]
+    fn test_old_versions_rejected() {
+        // Enregistrées quand le tir partait à chaque pas où il était enfoncé, puis sans hyperespace
+        for version in 1..Replay::VERSION {
+            let text = format!("asteroid-replay {}\nseed 5\nscreen 800 600\nconfig 0\n2 10\n", version);
+            let err = Replay::from_text(&text).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn test_other_screen_size_rejected() {
+        // Enregistrée quand le monde avait la taille de la fenêtre
+        let text = format!("asteroid-replay {}\nseed 5\nscreen 1024 768\nconfig 0\n2 10\n", Replay::VERSION);
+
+        let err = Replay::from_text(&text).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "taille de monde non supportée : 1024 768");
+    }
+
+    #[test]
+    fn test_unknown_version() {
+        let err = Replay::from_text("asteroid-replay 99\nseed 1\nscreen 800 600\n").unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_playback_reproduces_game() {
+        let mut config = GameConfig::default();
+        config.asteroid.speed = 90.0;
+        let mut world = GameWorld::with_config(7, config);
+        let mut replay = Replay::new(world.seed(), *world.config());
+        for i in 0..600 {
+            let input = PlayerInput { rotate_right: i % 3 == 0, fire: i % 15 == 0, thrust: i % 7 == 0, ..Default::default() };
+            replay.record(input);
+            world.step(&input);
+        }
+
+        let mut replayed = Replay::from_text(&replay.to_text()).unwrap().new_world();
+        for input in replay.inputs() {
+            replayed.step(input);
+        }
+
+        let positions = |w: &GameWorld| w.asteroids().iter().map(|a| a.get_position()).collect::<Vec<_>>();
+        assert_eq!(positions(&replayed), positions(&world));
+        assert_eq!(replayed.spaceship().get_position(), world.spaceship().get_position());
+        assert_eq!(replayed.spaceship().get_shield(), world.spaceship().get_shield());
+    }
+}
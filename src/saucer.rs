@@ -0,0 +1,245 @@
+use std::f32::consts::TAU;
+
+use macroquad::prelude::*;
+use ::rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::config::{MissileConfig, SaucerConfig};
+use crate::missile::Missile;
+use crate::stellarobject::StellarObject;
+use crate::torus;
+use crate::world::GameWorld;
+
+/// Les deux soucoupes volantes ennemies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SaucerKind {
+    /// Grande soucoupe qui tire au hasard.
+    Large,
+    /// Petite soucoupe qui vise le vaisseau.
+    Small,
+}
+
+/// Représente une soucoupe volante ennemie.
+///
+/// Elle entre par le bord gauche ou droit de l'écran, traverse l'écran en changeant
+/// régulièrement de cap verticalement et disparaît en sortant par le bord opposé.
+/// Ses missiles sont des [`Missile`] ordinaires, que la partie garde à part.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Saucer {
+    kind: SaucerKind,
+    position: Vec2,          // Position actuelle de la soucoupe
+    previous_position: Vec2, // Position au pas de simulation précédent
+    speed: Vec2,             // Vitesse de déplacement (unités par seconde)
+    fire_in: f32,            // Temps restant avant le prochain tir
+    course_in: f32,          // Temps restant avant le prochain changement de cap
+    is_active: bool,         // Faux une fois la soucoupe détruite ou sortie de l'écran
+    config: SaucerConfig,
+}
+
+impl Saucer {
+    /// Durée entre deux changements de cap, en secondes.
+    pub const COURSE_TIME: f32 = 1.0;
+
+    /// Crée une soucoupe avec les réglages par défaut.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Le type de soucoupe.
+    /// * `rng` - Le générateur aléatoire de la partie.
+    pub fn new(kind: SaucerKind, rng: &mut impl Rng) -> Self {
+        Self::with_config(kind, SaucerConfig::default(), rng)
+    }
+
+    /// Crée une soucoupe avec des réglages donnés, sur le bord gauche ou droit de l'écran
+    /// à une hauteur aléatoire.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Le type de soucoupe.
+    /// * `config` - Les réglages des soucoupes.
+    /// * `rng` - Le générateur aléatoire de la partie.
+    pub fn with_config(kind: SaucerKind, config: SaucerConfig, rng: &mut impl Rng) -> Self {
+        let from_left = rng.gen_bool(0.5);
+        let position = vec2(
+            if from_left { 0.0 } else { GameWorld::SIZE.0 },
+            rng.gen_range(0.0..GameWorld::SIZE.1),
+        );
+        let direction = if from_left { 1.0 } else { -1.0 };
+        Self {
+            kind,
+            position,
+            previous_position: position,
+            speed: vec2(direction * config.speed, 0.0),
+            fire_in: config.fire_interval,
+            course_in: Self::COURSE_TIME,
+            is_active: true,
+            config,
+        }
+    }
+
+    /// Retourne le type de la soucoupe.
+    pub fn kind(&self) -> SaucerKind {
+        self.kind
+    }
+
+    /// Indique si la soucoupe est encore en jeu.
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    /// Déplace la soucoupe à une nouvelle position.
+    ///
+    /// # Arguments
+    ///
+    /// * `new_pos` - La nouvelle position.
+    pub fn set_position(&mut self, new_pos: Vec2) {
+        self.position = new_pos;
+        self.previous_position = new_pos;
+    }
+
+    /// Retire la soucoupe du jeu, après une collision.
+    pub fn destroy(&mut self) {
+        self.is_active = false;
+    }
+
+    /// Fait agir la soucoupe : change de cap de temps en temps et tire quand
+    /// son arme est prête. La grande soucoupe tire dans une direction aléatoire,
+    /// la petite vise la cible à `saucer.aim_error` radians près.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - La durée du pas de simulation en secondes.
+    /// * `target` - La position du vaisseau.
+    /// * `rng` - Le générateur aléatoire de la partie.
+    ///
+    /// # Returns
+    ///
+    /// Le missile tiré pendant ce pas, s'il y en a un.
+    pub fn think(&mut self, dt: f32, target: Vec2, rng: &mut impl Rng) -> Option<Missile> {
+        if !self.is_active {
+            return None;
+        }
+        self.course_in -= dt;
+        if self.course_in <= 0.0 {
+            self.course_in = Self::COURSE_TIME;
+            // Monte, descend ou file droit, à la moitié de la vitesse horizontale
+            self.speed.y = rng.gen_range(-1..=1) as f32 * self.config.speed / 2.0;
+        }
+
+        self.fire_in -= dt;
+        if self.fire_in > 0.0 {
+            return None;
+        }
+        self.fire_in = self.config.fire_interval;
+        let orientation = match self.kind {
+            SaucerKind::Large => rng.gen_range(0.0..TAU),
+            SaucerKind::Small => {
+                let aim = target - self.position;
+                // Même convention d'angle que le vaisseau : 0 vers le haut, sens horaire
+                let error = self.config.aim_error;
+                aim.x.atan2(-aim.y) + if error > 0.0 { rng.gen_range(-error..=error) } else { 0.0 }
+            }
+        };
+        Some(Missile::with_config(
+            self.position,
+            orientation,
+            MissileConfig {
+                speed: self.config.missile_speed,
+                lifetime: self.config.missile_lifetime,
+                inherit_velocity: false,
+            },
+        ))
+    }
+}
+
+/// Implémentation du trait StellarObject pour la struct Saucer
+impl StellarObject for Saucer {
+    /// Retourne la position actuelle de la soucoupe.
+    fn get_position(&self) -> Vec2 {
+        self.position
+    }
+
+    /// Retourne la position de la soucoupe au pas précédent.
+    fn get_previous_position(&self) -> Vec2 {
+        self.previous_position
+    }
+
+    /// Déplace la soucoupe. Elle réapparaît de l'autre côté si elle sort par le haut
+    /// ou le bas, et quitte le jeu si elle sort par un côté.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - La durée du pas de simulation en secondes.
+    fn update(&mut self, dt: f32) {
+        self.previous_position = self.position;
+        self.position += self.speed * dt;
+        let moved = self.position;
+
+        if self.position.x < 0.0 || self.position.x > GameWorld::SIZE.0 {
+            self.is_active = false;
+        }
+        self.position.y = torus::wrap_coordinate(self.position.y, GameWorld::SIZE.1);
+        // Décale aussi la position précédente pour que l'interpolation ne traverse pas l'écran
+        self.previous_position += self.position - moved;
+    }
+
+    /// Retourne le rayon de la soucoupe, selon son type.
+    fn get_size(&self) -> f32 {
+        match self.kind {
+            SaucerKind::Large => self.config.large_size,
+            SaucerKind::Small => self.config.small_size,
+        }
+    }
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::rand::{rngs::SmallRng, SeedableRng};
+
+    const DT: f32 = 1.0 / 60.0;
+
+    #[test]
+    fn test_enters_from_side_and_leaves() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut saucer = Saucer::new(SaucerKind::Large, &mut rng);
+
+        assert!(saucer.get_position().x == 0.0 || saucer.get_position().x == 800.0);
+        assert_eq!(saucer.get_size(), SaucerConfig::default().large_size);
+
+        // La traversée de l'écran prend 800 / speed secondes
+        let crossing = 800.0 / SaucerConfig::default().speed;
+        let mut time = 0.0;
+        while saucer.is_active() {
+            saucer.update(DT);
+            time += DT;
+        }
+        assert!((time - crossing).abs() < 2.0 * DT);
+    }
+
+    #[test]
+    fn test_small_saucer_aims_at_target() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let config = SaucerConfig { aim_error: 0.0, ..SaucerConfig::default() };
+        let mut saucer = Saucer::with_config(SaucerKind::Small, config, &mut rng);
+        let target = vec2(400.0, 300.0);
+
+        let mut missile = (0..300).find_map(|_| saucer.think(DT, target, &mut rng)).unwrap();
+
+        let start = missile.get_position();
+        missile.update(DT);
+        let direction = (missile.get_position() - start).normalize();
+        assert!(direction.abs_diff_eq((target - start).normalize(), 1e-4));
+    }
+
+    #[test]
+    fn test_fire_interval() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut saucer = Saucer::new(SaucerKind::Large, &mut rng);
+
+        let ticks = (SaucerConfig::default().fire_interval * 3.5 / DT).round() as usize;
+        let shots = (0..ticks).filter(|_| saucer.think(DT, Vec2::ZERO, &mut rng).is_some()).count();
+
+        assert_eq!(shots, 3);
+    }
+}
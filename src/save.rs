@@ -0,0 +1,160 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::world::GameWorld;
+
+/// Version actuelle du format de sauvegarde, à augmenter quand une version publiée
+/// du jeu change la structure de la partie sauvegardée.
+pub const VERSION: u32 = 1;
+
+/// Contenu d'un fichier de sauvegarde : la version du format, la taille du monde
+/// et l'état de la partie.
+#[derive(Serialize, Deserialize)]
+struct SaveFile<W> {
+    version: u32,
+    screen_size: (f32, f32),
+    world: W,
+}
+
+/// En-tête d'un fichier de sauvegarde, lu seul pour vérifier la version avant
+/// d'interpréter la partie, dont la structure change d'une version à l'autre.
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+/// Sauvegarde une partie en cours dans un fichier JSON.
+///
+/// Le vaisseau, les astéroïdes, les missiles, le temps écoulé et l'état du
+/// générateur aléatoire sont enregistrés : la partie reprend exactement où elle s'était arrêtée.
+///
+/// # Arguments
+///
+/// * `world` - La partie à sauvegarder.
+/// * `path` - Le chemin du fichier à écrire.
+pub fn save_game(world: &GameWorld, path: impl AsRef<Path>) -> io::Result<()> {
+    let text = serde_json::to_string(&SaveFile { version: VERSION, screen_size: GameWorld::SIZE, world })?;
+    fs::write(path, text)
+}
+
+/// Recharge une partie sauvegardée par [`save_game`].
+///
+/// # Arguments
+///
+/// * `path` - Le chemin du fichier à lire.
+///
+/// # Returns
+///
+/// La partie sauvegardée, ou une erreur `InvalidData` si le fichier est mal formé,
+/// d'une version inconnue, d'une autre taille de monde que [`GameWorld::SIZE`] ou
+/// si ses réglages sont incohérents (voir [`crate::config::GameConfig::validate`]).
+pub fn load_game(path: impl AsRef<Path>) -> io::Result<GameWorld> {
+    let text = fs::read_to_string(path)?;
+    let header: SaveHeader = serde_json::from_str(&text)?;
+    if header.version != VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("version de sauvegarde non supportée : {}", header.version),
+        ));
+    }
+    let save: SaveFile<GameWorld> = serde_json::from_str(&text)?;
+    // Les positions sauvegardées n'ont de sens que dans un monde de la même taille
+    if save.screen_size != GameWorld::SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("taille de monde non supportée : {} {}", save.screen_size.0, save.screen_size.1),
+        ));
+    }
+    // Un fichier modifié à la main ne doit pas faire paniquer la partie reprise
+    save.world.config().validate().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    Ok(save.world)
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stellarobject::StellarObject;
+    use crate::world::PlayerInput;
+
+    #[test]
+    fn test_save_and_resume() {
+        let path = std::env::temp_dir().join("asteroid_test_save_and_resume.json");
+        let mut world = GameWorld::new(3);
+        let input = PlayerInput { thrust: true, rotate_right: true, fire: true, ..Default::default() };
+        for _ in 0..120 {
+            world.step(&input);
+        }
+
+        save_game(&world, &path).unwrap();
+        let mut resumed = load_game(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(resumed.elapsed(), world.elapsed());
+        // La partie reprise évolue exactement comme l'originale, aléatoire compris
+        for _ in 0..600 {
+            assert_eq!(resumed.step(&input), world.step(&input));
+        }
+        let positions = |w: &GameWorld| w.asteroids().iter().map(|a| a.get_position()).collect::<Vec<_>>();
+        assert_eq!(positions(&resumed), positions(&world));
+        assert_eq!(resumed.spaceship().get_position(), world.spaceship().get_position());
+        assert_eq!(resumed.missiles().len(), world.missiles().len());
+    }
+
+    #[test]
+    fn test_unknown_version() {
+        let path = std::env::temp_dir().join("asteroid_test_unknown_version.json");
+        let world = GameWorld::new(3);
+        let text = serde_json::to_string(&SaveFile { version: 99, screen_size: GameWorld::SIZE, world: &world }).unwrap();
+        fs::write(&path, text).unwrap();
+
+        let err = load_game(&path).err().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_other_screen_size() {
+        let path = std::env::temp_dir().join("asteroid_test_other_screen_size.json");
+        let world = GameWorld::new(3);
+        let text = serde_json::to_string(&SaveFile { version: VERSION, screen_size: (1024.0, 768.0), world: &world }).unwrap();
+        fs::write(&path, text).unwrap();
+
+        let err = load_game(&path).err().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "taille de monde non supportée : 1024 768");
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let path = std::env::temp_dir().join("asteroid_test_invalid_config.json");
+        let world = GameWorld::new(3);
+        save_game(&world, &path).unwrap();
+        let mut save: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let asteroid = &mut save["world"]["config"]["asteroid"];
+        asteroid["min_count"] = (asteroid["max_count"].as_u64().unwrap() + 1).into();
+        fs::write(&path, save.to_string()).unwrap();
+
+        let err = load_game(&path).err().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_malformed_world() {
+        let path = std::env::temp_dir().join("asteroid_test_malformed_world.json");
+        // Une partie incomplète, sans réglages ni vagues
+        let text = format!(r#"{{"version":{},"world":{{"spaceship":{{"position":[400.0,300.0]}},"asteroids":[],"missiles":[]}}}}"#, VERSION);
+        fs::write(&path, text).unwrap();
+
+        let err = load_game(&path).err().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -1,67 +1,126 @@
 use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::config::ShipConfig;
+use crate::polygon;
+use crate::torus;
+use crate::world::GameWorld;
 use crate::stellarobject::StellarObject;
 
 /// Représente un vaisseau spatial avec une position, une vitesse,
 /// une orientation et un niveau de bouclier.
+#[derive(Serialize, Deserialize)]
 pub struct Spaceship {
-    screen_size : (f32,f32),
     position: Vec2,
+    previous_position: Vec2,
     speed: Vec2,
     orientation: f32,
     shield: i32,
+    invulnerability: f32, // Temps d'invulnérabilité restant après une collision
+    respawning: bool,     // Vrai tant que le vaisseau touché attend de réapparaître
+    hyperspace: Option<f32>, // Temps restant dans l'hyperespace, pendant un saut
+    hyperspace_cooldown: f32, // Temps restant avant de pouvoir sauter à nouveau
+    config: ShipConfig,
 }
 
 impl Spaceship {
-    /// Def des constantes 
-    pub const MAX_SPEED: f32 = 50.0;
-    pub const MIN_SPEED: f32 = 0.05;
-    pub const ACCELERATION: f32 = 10.0;
-    pub const ROTATION_SPEED: f32 = 0.05;
-    pub const FRICTION: f32 = 0.99;
+    /// Def des constantes par défaut (distances en unités, durées en secondes),
+    /// remplaçables par un fichier de réglages (voir [`ShipConfig`]).
+    pub const MAX_SPEED: f32 = 3000.0;
+    pub const MIN_SPEED: f32 = 3.0;
+    pub const ACCELERATION: f32 = 600.0;
+    /// Vitesse de rotation en radians par seconde.
+    pub const ROTATION_SPEED: f32 = 3.0;
+    /// Fraction de la vitesse conservée au bout d'une seconde.
+    pub const FRICTION: f32 = 0.55;
     pub const INITIAL_SHIELD: i32 = 3;
+    /// Rayon du cercle englobant le vaisseau, pour ramasser les bonus.
+    pub const RADIUS: f32 = 15.0;
+    /// Contour triangulaire du vaisseau pointant vers le haut, tel qu'il est dessiné.
+    pub const SHAPE: [Vec2; 3] = [
+        Vec2::new(0.0, -15.0),
+        Vec2::new(10.0, 15.0),
+        Vec2::new(-10.0, 15.0),
+    ];
+    /// Durée d'invulnérabilité après une collision, en secondes.
+    pub const INVULNERABILITY_TIME: f32 = 2.0;
+    /// Rayon autour du centre qui doit être libre d'astéroïdes pour réapparaître.
+    pub const RESPAWN_CLEAR_RADIUS: f32 = 100.0;
+    /// Durée de la disparition lors d'un saut en hyperespace, en secondes.
+    pub const HYPERSPACE_TIME: f32 = 0.5;
+    /// Durée minimale entre deux sauts en hyperespace, en secondes.
+    pub const HYPERSPACE_COOLDOWN: f32 = 5.0;
+    /// Probabilité de réapparaître dans un astéroïde après un saut.
+    pub const HYPERSPACE_RISK: f64 = 0.1;
 
     /// Crée un nouveau vaisseau spatial au centre de l'écran avec des paramètres par défaut.
+    pub fn new() -> Self {
+        Self::with_config(ShipConfig::default())
+    }
+
+    /// Crée un nouveau vaisseau spatial au centre de l'écran avec des réglages donnés.
     /// 
     /// # Arguments
     /// 
-    /// * `screen_size` - taille de la fenetre de jeu.
-    pub fn new(screen_size:(f32,f32)) -> Self {
+    /// * `config` - les réglages du vaisseau.
+    pub fn with_config(config: ShipConfig) -> Self {
+        let position = vec2(GameWorld::SIZE.0 / 2.0, GameWorld::SIZE.1 / 2.0);
         Self {
-            screen_size,
-            position: vec2(screen_size.0 / 2.0, screen_size.1 / 2.0),
+            position,
+            previous_position: position,
             speed: vec2(0.0, 0.0),
             orientation: 0.0,
-            shield: Self::INITIAL_SHIELD,
+            shield: config.initial_shield,
+            invulnerability: 0.0,
+            respawning: false,
+            hyperspace: None,
+            hyperspace_cooldown: 0.0,
+            config,
         }
     }
 
     /// Active la poussée pour accélérer le vaisseau dans la direction actuelle de son orientation.
-    pub fn activate_thrust(&mut self) {
+    /// 
+    /// # Arguments
+    /// 
+    /// * `dt` - La durée du pas de simulation en secondes.
+    pub fn activate_thrust(&mut self, dt: f32) {
         let thrust = vec2(
-            self.orientation.sin() * Self::ACCELERATION*0.0168,
-            -self.orientation.cos() * Self::ACCELERATION*0.0168 ,
+            self.orientation.sin() * self.config.acceleration * dt,
+            -self.orientation.cos() * self.config.acceleration * dt,
         );
         self.speed += thrust;
 
         // Limite la vitesse maximale
-        if self.speed.length() > Self::MAX_SPEED {
-            self.speed = self.speed.normalize() * Self::MAX_SPEED;
+        if self.speed.length() > self.config.max_speed {
+            self.speed = self.speed.normalize() * self.config.max_speed;
         }
     }
 
     /// Applique une rétro-poussée pour ralentir le vaisseau
-    pub fn back_thrust(&mut self) {
-        self.speed *= Self::FRICTION;
+    /// 
+    /// # Arguments
+    /// 
+    /// * `dt` - La durée du pas de simulation en secondes.
+    pub fn back_thrust(&mut self, dt: f32) {
+        self.speed *= self.config.friction.powf(dt);
     }
 
     /// Tourne le vaisseau vers la gauche.
-    pub fn left_rotation(&mut self) {
-        self.orientation -= Self::ROTATION_SPEED;
+    /// 
+    /// # Arguments
+    /// 
+    /// * `dt` - La durée du pas de simulation en secondes.
+    pub fn left_rotation(&mut self, dt: f32) {
+        self.orientation -= self.config.rotation_speed * dt;
     }
 
     /// Tourne le vaisseau vers la droite.
-    pub fn right_rotation(&mut self) {
-        self.orientation += Self::ROTATION_SPEED;
+    /// 
+    /// # Arguments
+    /// 
+    /// * `dt` - La durée du pas de simulation en secondes.
+    pub fn right_rotation(&mut self, dt: f32) {
+        self.orientation += self.config.rotation_speed * dt;
     }
 
     /// Retourne l'orientation actuelle du vaisseau.
@@ -73,6 +132,15 @@ impl Spaceship {
         self.orientation
     }
 
+    /// Retourne la vitesse actuelle du vaisseau.
+    /// 
+    /// # Returns
+    /// 
+    /// La vitesse du vaisseau en unités par seconde.
+    pub fn get_speed(&self) -> Vec2 {
+        self.speed
+    }
+
     ///Retourne le niveau bouclier
     /// 
     /// # Returns
@@ -87,6 +155,91 @@ impl Spaceship {
         self.shield -= 1;
     }
 
+    /// Retourne le temps d'invulnérabilité restant après une collision.
+    /// 
+    /// # Returns
+    /// 
+    /// Le temps restant en secondes, 0 si le vaisseau n'est pas invulnérable.
+    pub fn get_invulnerability(&self) -> f32 {
+        self.invulnerability
+    }
+
+    /// Indique si le vaisseau ignore les collisions : pendant son invulnérabilité,
+    /// tant qu'il attend de réapparaître ou pendant un saut en hyperespace.
+    pub fn is_invulnerable(&self) -> bool {
+        !self.is_visible() || self.invulnerability > 0.0
+    }
+
+    /// Indique si le vaisseau est en jeu : ni en attente de réapparition, ni en hyperespace.
+    /// Un vaisseau invisible n'est ni affiché ni pilotable.
+    pub fn is_visible(&self) -> bool {
+        !self.respawning && self.hyperspace.is_none()
+    }
+
+    /// Indique si le vaisseau peut sauter en hyperespace.
+    pub fn can_jump(&self) -> bool {
+        self.is_visible() && self.hyperspace_cooldown <= 0.0
+    }
+
+    /// Retourne le temps restant avant de pouvoir sauter à nouveau en hyperespace.
+    /// 
+    /// # Returns
+    /// 
+    /// Le temps restant en secondes, 0 si le saut est disponible.
+    pub fn get_hyperspace_cooldown(&self) -> f32 {
+        self.hyperspace_cooldown
+    }
+
+    /// Fait disparaître le vaisseau dans l'hyperespace pendant `ship.hyperspace_time` secondes.
+    /// Il perd sa vitesse.
+    pub fn enter_hyperspace(&mut self) {
+        self.hyperspace = Some(self.config.hyperspace_time);
+        self.hyperspace_cooldown = self.config.hyperspace_cooldown;
+        self.speed = vec2(0.0, 0.0);
+    }
+
+    /// Indique si le vaisseau est en hyperespace.
+    pub fn is_in_hyperspace(&self) -> bool {
+        self.hyperspace.is_some()
+    }
+
+    /// Indique si le saut en hyperespace est terminé et que le vaisseau attend
+    /// sa position d'arrivée (voir [`Spaceship::exit_hyperspace`]).
+    pub fn is_hyperspace_over(&self) -> bool {
+        self.hyperspace.is_some_and(|remaining| remaining <= 0.0)
+    }
+
+    /// Fait réapparaître le vaisseau à la sortie de l'hyperespace.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `position` - La position d'arrivée.
+    pub fn exit_hyperspace(&mut self, position: Vec2) {
+        self.hyperspace = None;
+        self.position = position;
+        self.previous_position = position;
+    }
+
+    /// Indique si le vaisseau touché attend que le centre soit libre pour réapparaître.
+    /// Il n'est alors ni affiché ni pilotable.
+    pub fn is_respawning(&self) -> bool {
+        self.respawning
+    }
+
+    /// Fait réapparaître au centre le vaisseau qui attendait, et démarre son invulnérabilité.
+    pub fn respawn(&mut self) {
+        self.respawning = false;
+    }
+
+    /// Rend un niveau de bouclier, sans dépasser un maximum.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `max` - Le niveau de bouclier maximal.
+    pub fn recharge_shield(&mut self, max: i32) {
+        self.shield = (self.shield + 1).min(max).max(self.shield);
+    }
+
     /// Vérifie si le vaisseau est détruit (bouclier égal ou inférieur à zéro).
     /// 
     /// # Returns
@@ -97,6 +250,12 @@ impl Spaceship {
     }
 }
 
+impl Default for Spaceship {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Implémentation du trait StellarObject pour la struct Spaceship
 impl StellarObject for Spaceship {
     /// Retourne la position actuelle du vaisseau.
@@ -108,38 +267,53 @@ impl StellarObject for Spaceship {
         self.position
     }
 
+    /// Retourne la position du vaisseau au pas précédent.
+    fn get_previous_position(&self) -> Vec2 {
+        self.previous_position
+    }
 
     /// Met à jour la position du vaisseau, applique la friction et 
     /// gère le retour de l’autre côté de l’écran si le vaisseau dépasse les bords.
-    
-    fn update(&mut self) {
+    /// 
+    /// # Arguments
+    /// 
+    /// * `dt` - La durée du pas de simulation en secondes.
+    fn update(&mut self, dt: f32) {
+        self.previous_position = self.position;
+        // Le vaisseau en attente reste au centre, et son invulnérabilité ne décompte pas encore
+        if self.respawning {
+            return;
+        }
+        self.invulnerability = (self.invulnerability - dt).max(0.0);
+        self.hyperspace_cooldown = (self.hyperspace_cooldown - dt).max(0.0);
+        // Le vaisseau en hyperespace ne bouge pas
+        if let Some(remaining) = self.hyperspace.as_mut() {
+            *remaining -= dt;
+            return;
+        }
         // Applique la friction pour réduire progressivement la vitesse du vaisseau
-        self.speed *= Self::FRICTION;
+        self.speed *= self.config.friction.powf(dt);
 
         // Empêche la vitesse de descendre en dessous de la vitesse minimale
-        if self.speed.length() < Self::MIN_SPEED && self.speed.length() > 0.0 {
-            self.speed = self.speed.normalize() * Self::MIN_SPEED;
+        if self.speed.length() < self.config.min_speed && self.speed.length() > 0.0 {
+            self.speed = self.speed.normalize() * self.config.min_speed;
         }
 
         // Met à jour la position du vaisseau en fonction de la vitesse
-        self.position += self.speed;
+        self.position += self.speed * dt;
+        let moved = self.position;
 
         // Gestion des bords de l'écran : si le vaisseau sort d'un côté, il réapparaît de l'autre
-        if self.position.x < 0.0 {
-            self.position.x = self.screen_size.0;
-        } else if self.position.x > self.screen_size.0 {
-            self.position.x = 0.0;
-        }
-
-        if self.position.y < 0.0 {
-            self.position.y = self.screen_size.1;
-        } else if self.position.y > self.screen_size.1 {
-            self.position.y = 0.0;
-        }
+        self.position = torus::wrap(self.position, GameWorld::SIZE);
+        // Décale aussi la position précédente pour que l'interpolation ne traverse pas l'écran
+        self.previous_position += self.position - moved;
     }
 
     /// Vérifie la collision avec un astéroïde et gère la logique de bouclier.
-    /// En cas de collision, le bouclier est diminué de 1.
+    /// Le contour triangulaire du vaisseau est comparé au contour de l'autre objet,
+    /// ou à son cercle s'il n'a pas de contour.
+    /// En cas de collision, le bouclier est diminué de 1 et le vaisseau devient
+    /// invulnérable un moment. Les collisions sont ignorées pendant l'invulnérabilité.
     /// # Arguments
     /// * `_other` - Une réference a un dyn StelarObject.
     ///
@@ -147,77 +321,99 @@ impl StellarObject for Spaceship {
     /// 
     /// * `true` si une collision est détectée, `false` sinon.
     fn check_collision(&mut self, _other:&dyn StellarObject) -> bool {
-        let distance = self.position.distance(_other.get_position());
-        let radius = 15.0;
-        if distance< radius+_other.get_size() {
-            self.position = vec2(self.screen_size.0/ 2.0, self.screen_size.1/ 2.0);
+        if self.is_invulnerable() {
+            return false;
+        }
+        // L'autre objet est vu du côté du bord le plus proche du vaisseau
+        let center = _other.get_position();
+        let outline = torus::outline_near(_other.get_outline(), center, self.position, GameWorld::SIZE);
+        let collision = if outline.is_empty() {
+            let center = torus::nearest_image(center, self.position, GameWorld::SIZE);
+            polygon::circle_intersects_polygon(center, _other.get_size(), &self.get_outline())
+        } else {
+            polygon::polygons_intersect(&self.get_outline(), &outline)
+        };
+        if collision {
+            self.position = vec2(GameWorld::SIZE.0/ 2.0, GameWorld::SIZE.1/ 2.0);
+            self.previous_position = self.position;
             self.speed = vec2(0.0, 0.0);
             self.orientation = 0.0;
             self.decrease_shield();
+            self.invulnerability = self.config.invulnerability_time;
+            self.respawning = self.config.respawn_clear_radius > 0.0;
             return true;
         }
         false
     }
-    
+
+    /// Retourne le rayon du vaisseau.
+    fn get_size(&self) -> f32 {
+        Self::RADIUS
+    }
+
+    /// Retourne le contour triangulaire du vaisseau à l'écran.
+    fn get_outline(&self) -> Vec<Vec2> {
+        polygon::transform(&Self::SHAPE, self.position, self.orientation)
+    }
 }
 
 /// Tests unitaires 
 /// 
+#[cfg(test)]
+const DT: f32 = 1.0 / 60.0;
+#[cfg(test)]
+use ::rand::{rngs::SmallRng, SeedableRng};
+
 #[test]
 fn test_spaceship_new() {
-    let screen_size = (800.0,600.0);
-    let spaceship = Spaceship::new(screen_size);
+    let spaceship = Spaceship::new();
 
     assert_eq!(spaceship.position,vec2(400.0,300.0));
     assert_eq!(spaceship.speed,vec2(0.0, 0.0));
     assert_eq!(spaceship.orientation,0.0);
     assert_eq!(spaceship.shield,Spaceship::INITIAL_SHIELD);
-    assert_eq!(spaceship.screen_size,screen_size);
 }
 
 #[test]
 fn test_update() {
-    let screen_size = (800.0,600.0);
-    let mut spaceship = Spaceship::new(screen_size);
+    let mut spaceship = Spaceship::new();
 
-    spaceship.update();
+    spaceship.update(DT);
     spaceship.position = vec2(-10.0, 300.0); // Hors de l'écran à gauche
-    spaceship.update();
-    assert_eq!(spaceship.position.x, 800.0);
+    spaceship.update(DT);
+    // Il réapparaît à la même distance du bord droit
+    assert_eq!(spaceship.position.x, 790.0);
 
 }
 
 #[test]
 fn test_spaceship_thrust() {
-    let screen_size = (800.0, 600.0);
-    let mut spaceship = Spaceship::new(screen_size);
+    let mut spaceship = Spaceship::new();
 
-    spaceship.activate_thrust();
+    spaceship.activate_thrust(DT);
     assert!(spaceship.speed.length() > 0.0);
 
     let initial_speed = spaceship.speed.length();
-    spaceship.activate_thrust();
+    spaceship.activate_thrust(DT);
     assert!(spaceship.speed.length() > initial_speed);
 }
 
 #[test]
 fn test_spaceship_rotation() {
-    let screen_size = (800.0, 600.0);
-    let mut spaceship = Spaceship::new(screen_size);
+    let mut spaceship = Spaceship::new();
 
-    spaceship.right_rotation();
+    spaceship.right_rotation(DT);
     assert!(spaceship.orientation > 0.0);
 
     let orientation_after_right = spaceship.orientation;
-    spaceship.left_rotation();
+    spaceship.left_rotation(DT);
     assert!(spaceship.orientation < orientation_after_right);
 }
 #[test]
 fn test_spaceship_collision() {
-    let screen_size = (800.0, 600.0);
-    let mut spaceship = Spaceship::new(screen_size);
+    let mut spaceship = Spaceship::new();
 
-    let mut asteroid=crate::Asteroid::new(20.0, screen_size);
+    let mut asteroid=crate::Asteroid::new(20.0, &mut SmallRng::seed_from_u64(0));
     asteroid.set_position(vec2(400.0, 300.0));// Position identique au vaisseau
 
     let initial_shield = spaceship.shield;
@@ -231,8 +427,7 @@ fn test_spaceship_collision() {
 
 #[test]
 fn test_spaceship_destruction() {
-    let screen_size = (800.0, 600.0);
-    let mut spaceship = Spaceship::new(screen_size);
+    let mut spaceship = Spaceship::new();
 
     spaceship.shield = 1;
     spaceship.decrease_shield();
@@ -240,4 +435,63 @@ fn test_spaceship_destruction() {
 
     spaceship.shield = 0;
     assert!(spaceship.is_destroyed());
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_spaceship_invulnerability() {
+    let mut spaceship = Spaceship::new();
+    let mut asteroid=crate::Asteroid::new(20.0, &mut SmallRng::seed_from_u64(0));
+    asteroid.set_position(vec2(400.0, 300.0));
+
+    assert!(spaceship.check_collision(&asteroid));
+    assert!(spaceship.is_respawning());
+    // L'astéroïde encore au centre ne retire pas d'autre niveau de bouclier
+    assert!(!spaceship.check_collision(&asteroid));
+    assert_eq!(spaceship.shield, Spaceship::INITIAL_SHIELD - 1);
+
+    // L'invulnérabilité ne décompte qu'après la réapparition
+    spaceship.update(Spaceship::INVULNERABILITY_TIME);
+    assert!(spaceship.is_invulnerable());
+    spaceship.respawn();
+    spaceship.update(DT);
+    assert!(spaceship.is_invulnerable());
+    spaceship.update(Spaceship::INVULNERABILITY_TIME);
+    assert!(!spaceship.is_invulnerable());
+    assert!(spaceship.check_collision(&asteroid));
+}
+
+#[test]
+fn test_spaceship_hyperspace() {
+    let mut spaceship = Spaceship::new();
+    spaceship.activate_thrust(DT);
+
+    assert!(spaceship.can_jump());
+    spaceship.enter_hyperspace();
+    assert!(!spaceship.is_visible());
+    assert!(spaceship.is_invulnerable());
+    assert!(!spaceship.can_jump());
+    assert_eq!(spaceship.speed, vec2(0.0, 0.0));
+
+    spaceship.update(Spaceship::HYPERSPACE_TIME);
+    assert!(spaceship.is_hyperspace_over());
+    spaceship.exit_hyperspace(vec2(100.0, 100.0));
+    assert!(spaceship.is_visible());
+    assert_eq!(spaceship.get_position(), vec2(100.0, 100.0));
+
+    // Le saut suivant attend la fin du délai
+    assert!(!spaceship.can_jump());
+    spaceship.update(Spaceship::HYPERSPACE_COOLDOWN);
+    assert!(spaceship.can_jump());
+}
+
+#[test]
+fn test_spaceship_collision_across_edge() {
+    let mut spaceship = Spaceship::new();
+    spaceship.position = vec2(5.0, 300.0);
+
+    // Astéroïde à cheval sur le bord droit, tout près du vaisseau à travers le bord
+    let mut asteroid=crate::Asteroid::new(20.0, &mut SmallRng::seed_from_u64(0));
+    asteroid.set_position(vec2(795.0, 300.0));
+
+    assert!(spaceship.check_collision(&asteroid));
+}
@@ -0,0 +1,149 @@
+//! Grille uniforme pour la détection large des collisions (broadphase).
+//!
+//! Les objets sont rangés, à chaque pas, dans les cases de la grille que couvre leur
+//! cercle englobant. Pour trouver ce qui peut toucher un objet, il suffit de regarder
+//! les cases voisines au lieu de parcourir tous les objets. L'écran boucle sur lui-même :
+//! les cases du bord droit touchent celles du bord gauche, et de même en hauteur.
+use macroquad::prelude::*;
+
+/// Grille de hachage spatial sur un écran qui boucle sur lui-même.
+///
+/// La grille ne connaît que les indices des objets dans la liste de l'appelant ;
+/// elle se vide et se remplit à chaque pas de simulation.
+#[derive(Clone, Debug)]
+pub struct SpatialHash {
+    cell_size: Vec2, // Taille réelle d'une case, qui divise exactement l'écran
+    columns: usize,
+    rows: usize,
+    cells: Vec<Vec<usize>>, // Indices des objets de chaque case, ligne par ligne
+}
+
+impl SpatialHash {
+    /// Crée une grille vide qui couvre l'écran.
+    ///
+    /// # Arguments
+    ///
+    /// * `screen_size` - La taille de la fenetre de jeu.
+    /// * `cell_size` - La taille minimale d'une case, de l'ordre du diamètre des plus gros
+    ///   objets. Les cases sont agrandies pour que l'écran en contienne un nombre entier
+    ///   dans chaque direction : la dernière colonne touche alors exactement la première.
+    pub fn new(screen_size: (f32, f32), cell_size: f32) -> Self {
+        let columns = (screen_size.0 / cell_size).floor().max(1.0) as usize;
+        let rows = (screen_size.1 / cell_size).floor().max(1.0) as usize;
+        let cell_size = vec2(screen_size.0 / columns as f32, screen_size.1 / rows as f32);
+        Self { cell_size, columns, rows, cells: vec![Vec::new(); columns * rows] }
+    }
+
+    /// Vide la grille, en gardant la mémoire des cases pour le pas suivant.
+    pub fn clear(&mut self) {
+        for cell in self.cells.iter_mut() {
+            cell.clear();
+        }
+    }
+
+    /// Range un objet dans toutes les cases que couvre son cercle englobant.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - L'indice de l'objet dans la liste de l'appelant.
+    /// * `position` - La position du centre de l'objet.
+    /// * `radius` - Le rayon du cercle englobant (0 pour un point).
+    pub fn insert(&mut self, index: usize, position: Vec2, radius: f32) {
+        for cell in self.covered_cells(position, radius) {
+            self.cells[cell].push(index);
+        }
+    }
+
+    /// Cherche les objets dont les cases touchent un cercle. Ce sont des candidats :
+    /// il reste à vérifier la collision elle-même.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - Le centre du cercle.
+    /// * `radius` - Le rayon du cercle.
+    /// * `found` - Reçoit les indices des candidats, triés et sans doublon.
+    pub fn query(&self, position: Vec2, radius: f32, found: &mut Vec<usize>) {
+        found.clear();
+        for cell in self.covered_cells(position, radius) {
+            found.extend_from_slice(&self.cells[cell]);
+        }
+        found.sort_unstable();
+        found.dedup();
+    }
+
+    /// Parcourt les cases couvertes par le carré englobant d'un cercle, en bouclant
+    /// sur les bords de l'écran.
+    fn covered_cells(&self, position: Vec2, radius: f32) -> impl Iterator<Item = usize> {
+        let (columns, rows) = (self.columns, self.rows);
+        let x = Self::span(position.x - radius, position.x + radius, self.cell_size.x, columns);
+        let y = Self::span(position.y - radius, position.y + radius, self.cell_size.y, rows);
+        y.flat_map(move |row| {
+            let x = x.clone();
+            x.map(move |column| row.rem_euclid(rows as i64) as usize * columns + column.rem_euclid(columns as i64) as usize)
+        })
+    }
+
+    /// Retourne la plage d'indices de cases entre deux coordonnées, sans faire
+    /// plusieurs fois le tour de l'écran.
+    fn span(min: f32, max: f32, cell_size: f32, count: usize) -> std::ops::Range<i64> {
+        let first = (min / cell_size).floor() as i64;
+        let last = (max / cell_size).floor() as i64;
+        first..(last + 1).min(first + count as i64)
+    }
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_finds_neighbours_only() {
+        let mut grid = SpatialHash::new((800.0, 600.0), 100.0);
+        grid.insert(0, vec2(150.0, 150.0), 10.0);
+        grid.insert(1, vec2(650.0, 450.0), 10.0);
+        let mut found = Vec::new();
+
+        grid.query(vec2(180.0, 120.0), 20.0, &mut found);
+        assert_eq!(found, vec![0]);
+        grid.query(vec2(400.0, 300.0), 20.0, &mut found);
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn test_query_wraps_around_edges() {
+        let mut grid = SpatialHash::new((800.0, 600.0), 100.0);
+        // Un objet à cheval sur le coin en haut à gauche
+        grid.insert(0, vec2(5.0, 5.0), 20.0);
+        let mut found = Vec::new();
+
+        grid.query(vec2(795.0, 595.0), 10.0, &mut found);
+        assert_eq!(found, vec![0]);
+    }
+
+    #[test]
+    fn test_wraps_when_cells_do_not_divide_screen() {
+        // 800 / 110 : sept colonnes de 114 et quelques, et non huit qui déborderaient de l'écran
+        let mut grid = SpatialHash::new((800.0, 600.0), 110.0);
+        grid.insert(0, vec2(760.0, 300.0), 0.0);
+        let mut found = Vec::new();
+
+        grid.query(vec2(10.0, 300.0), 55.0, &mut found);
+        assert_eq!(found, vec![0]);
+        grid.insert(1, vec2(300.0, 5.0), 0.0);
+        grid.query(vec2(290.0, 590.0), 55.0, &mut found);
+        assert_eq!(found, vec![1]);
+    }
+
+    #[test]
+    fn test_large_object_listed_once() {
+        let mut grid = SpatialHash::new((800.0, 600.0), 100.0);
+        // Plus grand que l'écran : il couvre chaque case une seule fois
+        grid.insert(0, vec2(400.0, 300.0), 1000.0);
+        let mut found = Vec::new();
+
+        grid.query(vec2(0.0, 0.0), 0.0, &mut found);
+        assert_eq!(found, vec![0]);
+        assert!(grid.cells.iter().all(|cell| cell == &[0]));
+    }
+}
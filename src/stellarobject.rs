@@ -4,8 +4,20 @@ pub trait StellarObject{
     /// Renvoie la position Acutuelle de l'objet
     fn get_position(&self)->Vec2;
 
-    /// Met à jour l'état de l'objet (déplacement)
-    fn update(&mut self);
+    /// Renvoie la position de l'objet au pas de simulation précédent.
+    /// Par Défaut Retourne la position actuelle si elle n'est pas surchargé.
+    fn get_previous_position(&self)->Vec2{
+        self.get_position()
+    }
+
+    /// Renvoie la position interpolée entre le pas précédent (`alpha` = 0.0)
+    /// et le pas actuel (`alpha` = 1.0), utilisée pour l'affichage.
+    fn get_interpolated_position(&self, alpha:f32)->Vec2{
+        self.get_previous_position().lerp(self.get_position(), alpha)
+    }
+
+    /// Met à jour l'état de l'objet (déplacement) pour un pas de `dt` secondes.
+    fn update(&mut self, dt:f32);
 
     /// Vérifie si cet objet stellaire est en collision avec un autre.
     /// Retourne `true` si une collision est détectée, `false` sinon.
@@ -19,5 +31,12 @@ pub trait StellarObject{
     fn get_size(&self)->f32{
         0.0
     }
+
+    /// Renvoie le contour polygonal de l'objet à l'écran, utilisé pour des collisions
+    /// fidèles à ce qui est dessiné (voir [`crate::polygon`]).
+    /// Par Défaut Retourne un contour vide : l'objet est alors un cercle de rayon `get_size`.
+    fn get_outline(&self)->Vec<Vec2>{
+        Vec::new()
+    }
   
-}
\ No newline at end of file
+}
\ No newline at end of file
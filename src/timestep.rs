@@ -0,0 +1,89 @@
+/// Accumulateur pour une simulation à pas fixe.
+///
+/// Le temps réel écoulé entre deux images est accumulé puis consommé par pas
+/// de durée fixe, ce qui rend la simulation indépendante de la fréquence d'affichage.
+pub struct FixedTimestep {
+    tick: f32,        // Durée d'un pas de simulation en secondes
+    accumulator: f32, // Temps écoulé pas encore simulé
+}
+
+impl FixedTimestep {
+    /// Durée maximale prise en compte pour une image, afin d'éviter
+    /// d'enchaîner des centaines de pas après une longue pause.
+    pub const MAX_FRAME_TIME: f32 = 0.25;
+
+    /// Crée un accumulateur vide.
+    ///
+    /// # Arguments
+    ///
+    /// * `tick` - La durée d'un pas de simulation en secondes.
+    pub fn new(tick: f32) -> Self {
+        Self { tick, accumulator: 0.0 }
+    }
+
+    /// Retourne la durée d'un pas de simulation.
+    pub fn tick(&self) -> f32 {
+        self.tick
+    }
+
+    /// Ajoute le temps d'une image à l'accumulateur.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame_time` - Le temps écoulé depuis l'image précédente, en secondes.
+    ///
+    /// # Returns
+    ///
+    /// Le nombre de pas de simulation à exécuter pour cette image.
+    pub fn advance(&mut self, frame_time: f32) -> u32 {
+        self.accumulator += frame_time.clamp(0.0, Self::MAX_FRAME_TIME);
+        let mut steps = 0;
+        while self.accumulator >= self.tick {
+            self.accumulator -= self.tick;
+            steps += 1;
+        }
+        steps
+    }
+
+    /// Retourne la fraction du pas suivant déjà écoulée, entre 0.0 et 1.0.
+    /// Sert à interpoler l'affichage entre deux pas.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.tick
+    }
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_independent_of_frame_rate() {
+        let tick = 1.0 / 60.0;
+        let mut at_60hz = FixedTimestep::new(tick);
+        let mut at_144hz = FixedTimestep::new(tick);
+
+        // Une seconde simulée à 60 Hz et à 144 Hz
+        let steps_60: u32 = (0..60).map(|_| at_60hz.advance(1.0 / 60.0)).sum();
+        let steps_144: u32 = (0..144).map(|_| at_144hz.advance(1.0 / 144.0)).sum();
+
+        assert!((59..=60).contains(&steps_60));
+        assert!((59..=60).contains(&steps_144));
+    }
+
+    #[test]
+    fn test_alpha() {
+        let mut timestep = FixedTimestep::new(0.1);
+
+        assert_eq!(timestep.advance(0.25), 2);
+        assert!((timestep.alpha() - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_frame_time_clamped() {
+        let mut timestep = FixedTimestep::new(0.1);
+
+        // Une pause de 10 secondes ne simule pas plus que MAX_FRAME_TIME
+        assert_eq!(timestep.advance(10.0), 2);
+    }
+}
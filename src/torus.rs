@@ -0,0 +1,151 @@
+//! Géométrie de l'écran torique : ce qui sort d'un côté réapparaît du côté opposé.
+//!
+//! Toutes les positions sont ramenées dans `[0, largeur[ × [0, hauteur[`. Deux objets
+//! séparés par un bord sont proches l'un de l'autre : les distances et les collisions
+//! passent par le chemin le plus court, en traversant le bord si besoin.
+use macroquad::prelude::*;
+
+/// Ramène une coordonnée dans `[0, max[`.
+///
+/// # Arguments
+///
+/// * `coord` - La coordonnée, éventuellement hors de l'écran.
+/// * `max` - La taille de l'écran sur cet axe.
+pub fn wrap_coordinate(coord: f32, max: f32) -> f32 {
+    let wrapped = coord.rem_euclid(max);
+    // Un tout petit nombre négatif donne `max` à cause de l'arrondi
+    if wrapped >= max { 0.0 } else { wrapped }
+}
+
+/// Ramène une position sur l'écran.
+///
+/// # Arguments
+///
+/// * `position` - La position, éventuellement hors de l'écran.
+/// * `screen_size` - La taille de la fenetre de jeu.
+pub fn wrap(position: Vec2, screen_size: (f32, f32)) -> Vec2 {
+    vec2(wrap_coordinate(position.x, screen_size.0), wrap_coordinate(position.y, screen_size.1))
+}
+
+/// Retourne le plus court déplacement d'un point à un autre, en traversant
+/// les bords de l'écran si c'est plus court.
+///
+/// # Arguments
+///
+/// * `from` - Le point de départ.
+/// * `to` - Le point d'arrivée.
+/// * `screen_size` - La taille de la fenetre de jeu.
+pub fn displacement(from: Vec2, to: Vec2, screen_size: (f32, f32)) -> Vec2 {
+    let shortest = |d: f32, max: f32| d - max * (d / max).round();
+    let d = to - from;
+    vec2(shortest(d.x, screen_size.0), shortest(d.y, screen_size.1))
+}
+
+/// Retourne la plus courte distance entre deux points de l'écran.
+///
+/// # Arguments
+///
+/// * `a` - Le premier point.
+/// * `b` - Le second point.
+/// * `screen_size` - La taille de la fenetre de jeu.
+pub fn distance(a: Vec2, b: Vec2, screen_size: (f32, f32)) -> f32 {
+    displacement(a, b, screen_size).length()
+}
+
+/// Retourne la copie d'un point la plus proche d'une référence, éventuellement
+/// hors de l'écran : c'est là qu'il faut le voir pour un test de collision.
+///
+/// # Arguments
+///
+/// * `point` - Le point à déplacer.
+/// * `reference` - La position de référence.
+/// * `screen_size` - La taille de la fenetre de jeu.
+pub fn nearest_image(point: Vec2, reference: Vec2, screen_size: (f32, f32)) -> Vec2 {
+    reference + displacement(reference, point, screen_size)
+}
+
+/// Déplace un contour autour de la copie de son centre la plus proche d'une référence,
+/// pour tester une collision à travers un bord de l'écran.
+///
+/// # Arguments
+///
+/// * `outline` - Les sommets du contour à l'écran.
+/// * `center` - Le centre du contour.
+/// * `reference` - La position de référence.
+/// * `screen_size` - La taille de la fenetre de jeu.
+pub fn outline_near(outline: Vec<Vec2>, center: Vec2, reference: Vec2, screen_size: (f32, f32)) -> Vec<Vec2> {
+    let offset = nearest_image(center, reference, screen_size) - center;
+    if offset == Vec2::ZERO {
+        return outline;
+    }
+    outline.into_iter().map(|point| point + offset).collect()
+}
+
+/// Retourne les positions où dessiner un objet pour qu'il apparaisse des deux côtés
+/// d'un bord qu'il chevauche : la sienne, et ses copies décalées d'une largeur ou d'une
+/// hauteur d'écran qui touchent encore l'écran.
+///
+/// # Arguments
+///
+/// * `position` - La position de l'objet.
+/// * `radius` - Le rayon du cercle englobant de l'objet.
+/// * `screen_size` - La taille de la fenetre de jeu.
+pub fn images(position: Vec2, radius: f32, screen_size: (f32, f32)) -> Vec<Vec2> {
+    let (width, height) = screen_size;
+    let mut images = Vec::with_capacity(1);
+    for dy in [0.0, -height, height] {
+        for dx in [0.0, -width, width] {
+            let image = position + vec2(dx, dy);
+            let visible = image.x + radius > 0.0
+                && image.x - radius < width
+                && image.y + radius > 0.0
+                && image.y - radius < height;
+            // La position de l'objet est toujours dessinée, même hors de l'écran
+            if (dx == 0.0 && dy == 0.0) || visible {
+                images.push(image);
+            }
+        }
+    }
+    images
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: (f32, f32) = (800.0, 600.0);
+
+    #[test]
+    fn test_wrap() {
+        // Sortir de 10 à gauche ramène à 10 du bord droit
+        assert_eq!(wrap(vec2(-10.0, 610.0), SCREEN), vec2(790.0, 10.0));
+        assert_eq!(wrap(vec2(800.0, 0.0), SCREEN), vec2(0.0, 0.0));
+        assert_eq!(wrap_coordinate(-1e-6, 800.0), 0.0);
+    }
+
+    #[test]
+    fn test_displacement_across_edge() {
+        let d = displacement(vec2(790.0, 300.0), vec2(10.0, 300.0), SCREEN);
+
+        assert_eq!(d, vec2(20.0, 0.0));
+        assert_eq!(distance(vec2(5.0, 5.0), vec2(795.0, 595.0), SCREEN), 200.0f32.sqrt());
+        assert_eq!(distance(vec2(100.0, 100.0), vec2(200.0, 100.0), SCREEN), 100.0);
+    }
+
+    #[test]
+    fn test_outline_near() {
+        let outline = vec![vec2(790.0, 300.0), vec2(810.0, 300.0)];
+
+        let moved = outline_near(outline, vec2(800.0, 300.0), vec2(5.0, 300.0), SCREEN);
+
+        assert_eq!(moved, vec![vec2(-10.0, 300.0), vec2(10.0, 300.0)]);
+    }
+
+    #[test]
+    fn test_images() {
+        assert_eq!(images(vec2(400.0, 300.0), 50.0, SCREEN), vec![vec2(400.0, 300.0)]);
+        // Dans le coin : dessiné dans les quatre coins
+        assert_eq!(images(vec2(10.0, 10.0), 20.0, SCREEN).len(), 4);
+    }
+}
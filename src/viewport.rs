@@ -0,0 +1,73 @@
+//! Mise à l'échelle du monde de jeu dans la fenêtre.
+//!
+//! La partie se déroule dans un monde de taille fixe ([`GameWorld::SIZE`](crate::GameWorld::SIZE)),
+//! quelle que soit la fenêtre : le monde est agrandi ou réduit pour tenir dans la fenêtre
+//! sans être déformé, et centré entre des bandes noires (letterboxing).
+use macroquad::prelude::*;
+
+/// Placement du monde de jeu dans la fenêtre.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Letterbox {
+    /// Facteur d'échelle entre une unité du monde et un pixel de la fenêtre.
+    pub scale: f32,
+    /// Position du coin haut gauche du monde dans la fenêtre, en pixels.
+    pub offset: Vec2,
+    /// Taille du monde dans la fenêtre, en pixels.
+    pub size: Vec2,
+}
+
+impl Letterbox {
+    /// Calcule le plus grand placement du monde qui tient dans la fenêtre, centré.
+    ///
+    /// # Arguments
+    ///
+    /// * `world_size` - La taille du monde de jeu.
+    /// * `window_size` - La taille de la fenêtre en pixels.
+    pub fn fit(world_size: (f32, f32), window_size: (f32, f32)) -> Self {
+        let world = vec2(world_size.0, world_size.1);
+        let window = vec2(window_size.0, window_size.1);
+        let scale = (window / world).min_element();
+        let size = world * scale;
+        Self { scale, offset: (window - size) / 2.0, size }
+    }
+
+    /// Retourne la zone d'affichage du monde au format d'OpenGL : en pixels physiques,
+    /// depuis le coin bas gauche de la fenêtre.
+    ///
+    /// # Arguments
+    ///
+    /// * `dpi_scale` - Le nombre de pixels physiques par pixel de la fenêtre.
+    pub fn gl_viewport(&self, dpi_scale: f32) -> (i32, i32, i32, i32) {
+        // Le monde est centré : la bande du bas est aussi haute que celle du haut
+        let offset = self.offset * dpi_scale;
+        let size = self.size * dpi_scale;
+        (offset.x.round() as i32, offset.y.round() as i32, size.x.round() as i32, size.y.round() as i32)
+    }
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_wide_window() {
+        let letterbox = Letterbox::fit((800.0, 600.0), (1920.0, 1080.0));
+
+        // Bandes à gauche et à droite
+        assert_eq!(letterbox.scale, 1.8);
+        assert_eq!(letterbox.size, vec2(1440.0, 1080.0));
+        assert_eq!(letterbox.offset, vec2(240.0, 0.0));
+        assert_eq!(letterbox.gl_viewport(2.0), (480, 0, 2880, 2160));
+    }
+
+    #[test]
+    fn test_fit_tall_window() {
+        let letterbox = Letterbox::fit((800.0, 600.0), (400.0, 600.0));
+
+        // Bandes en haut et en bas
+        assert_eq!(letterbox.scale, 0.5);
+        assert_eq!(letterbox.offset, vec2(0.0, 150.0));
+        assert_eq!(letterbox.gl_viewport(1.0), (0, 150, 400, 300));
+    }
+}
@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use crate::config::WeaponConfig;
+
+/// Arme du vaisseau : cadence de tir limitée, nombre de missiles en vol plafonné
+/// et jauge de chaleur qui bloque le tir en cas de surchauffe.
+///
+/// Chaque tir ajoute `weapon.heat_per_shot` à la jauge, qui refroidit de
+/// `weapon.cooling_rate` par seconde. Quand elle atteint 1, l'arme surchauffe
+/// et ne tire plus jusqu'à ce que la jauge soit revenue à 0.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Weapon {
+    cooldown: f32,     // Temps restant avant le prochain tir possible
+    heat: f32,         // Chaleur accumulée, entre 0 et 1
+    overheated: bool,  // Vrai de la surchauffe jusqu'au refroidissement complet
+    config: WeaponConfig,
+}
+
+impl Weapon {
+    /// Crée une arme froide, prête à tirer.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Les réglages de l'arme.
+    pub fn with_config(config: WeaponConfig) -> Self {
+        Self { cooldown: 0.0, heat: 0.0, overheated: false, config }
+    }
+
+    /// Retourne le temps restant avant le prochain tir possible.
+    pub fn cooldown(&self) -> f32 {
+        self.cooldown
+    }
+
+    /// Retourne la chaleur accumulée, entre 0 et 1.
+    pub fn heat(&self) -> f32 {
+        self.heat
+    }
+
+    /// Indique si l'arme est en surchauffe.
+    pub fn is_overheated(&self) -> bool {
+        self.overheated
+    }
+
+    /// Retourne le nombre maximal de missiles du joueur en vol.
+    pub fn max_missiles(&self) -> usize {
+        self.config.max_missiles as usize
+    }
+
+    /// Indique si l'arme peut tirer.
+    ///
+    /// # Arguments
+    ///
+    /// * `missiles_in_flight` - Le nombre de missiles du joueur encore en vol.
+    pub fn can_fire(&self, missiles_in_flight: usize) -> bool {
+        self.cooldown <= 0.0 && !self.overheated && missiles_in_flight < self.max_missiles()
+    }
+
+    /// Enregistre un tir : l'arme chauffe et attend `interval` secondes avant le suivant.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - La durée avant le prochain tir possible, `weapon.cooldown`
+    ///   en temps normal.
+    pub fn fire(&mut self, interval: f32) {
+        self.cooldown = interval;
+        self.heat = (self.heat + self.config.heat_per_shot).min(1.0);
+        if self.heat >= 1.0 {
+            self.overheated = true;
+        }
+    }
+
+    /// Décompte le délai entre deux tirs et refroidit l'arme.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - La durée du pas de simulation en secondes.
+    pub fn update(&mut self, dt: f32) {
+        self.cooldown = (self.cooldown - dt).max(0.0);
+        self.heat = (self.heat - self.config.cooling_rate * dt).max(0.0);
+        if self.heat <= 0.0 {
+            self.overheated = false;
+        }
+    }
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cooldown_and_cap() {
+        let config = WeaponConfig::default();
+        let mut weapon = Weapon::with_config(config);
+
+        assert!(weapon.can_fire(0));
+        weapon.fire(config.cooldown);
+        assert!(!weapon.can_fire(0));
+        weapon.update(config.cooldown);
+        assert!(weapon.can_fire(0));
+        assert!(!weapon.can_fire(config.max_missiles as usize));
+    }
+
+    #[test]
+    fn test_overheat() {
+        let config = WeaponConfig { cooldown: 0.0, heat_per_shot: 0.5, cooling_rate: 1.0, ..WeaponConfig::default() };
+        let mut weapon = Weapon::with_config(config);
+
+        weapon.fire(0.0);
+        assert!(!weapon.is_overheated());
+        weapon.fire(0.0);
+        assert!(weapon.is_overheated());
+        assert!(!weapon.can_fire(0));
+
+        // La surchauffe dure jusqu'au refroidissement complet
+        weapon.update(0.5);
+        assert!(weapon.is_overheated());
+        weapon.update(0.5);
+        assert!(!weapon.is_overheated());
+        assert!(weapon.can_fire(0));
+    }
+
+    #[test]
+    fn test_no_heat() {
+        let config = WeaponConfig { cooldown: 0.0, heat_per_shot: 0.0, ..WeaponConfig::default() };
+        let mut weapon = Weapon::with_config(config);
+
+        for _ in 0..100 {
+            weapon.fire(0.0);
+        }
+        assert!(weapon.can_fire(0));
+    }
+}
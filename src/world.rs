@@ -0,0 +1,1102 @@
+use std::time::Duration;
+use macroquad::prelude::*;
+use ::rand::{Rng, SeedableRng};
+use rand_xoshiro::Xoshiro256PlusPlus;
+use serde::{Deserialize, Serialize};
+use crate::asteroid::Asteroid;
+use crate::config::GameConfig;
+use crate::missile::Missile;
+use crate::powerup::{ActiveEffects, PowerUp, PowerUpKind};
+use crate::saucer::{Saucer, SaucerKind};
+use crate::spaceship::Spaceship;
+use crate::spatialhash::SpatialHash;
+use crate::stellarobject::StellarObject;
+use crate::torus;
+use crate::weapon::Weapon;
+
+/// Commandes du joueur appliquées lors d'un pas de simulation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PlayerInput {
+    /// Poussée vers l'avant.
+    pub thrust: bool,
+    /// Rétro-poussée (freinage).
+    pub back_thrust: bool,
+    /// Rotation vers la gauche.
+    pub rotate_left: bool,
+    /// Rotation vers la droite.
+    pub rotate_right: bool,
+    /// Bouton de tir enfoncé : un missile part à l'appui si l'arme est prête, puis à
+    /// intervalles réguliers tant qu'il reste enfoncé sous l'effet du tir rapide.
+    pub fire: bool,
+    /// Saut en hyperespace.
+    pub hyperspace: bool,
+}
+
+impl PlayerInput {
+    /// Encode les commandes sur 6 bits (haut, bas, gauche, droite, tir, hyperespace).
+    ///
+    /// # Returns
+    ///
+    /// Les commandes encodées, utilisées notamment par les replays.
+    pub fn to_bits(&self) -> u8 {
+        (self.thrust as u8)
+            | (self.back_thrust as u8) << 1
+            | (self.rotate_left as u8) << 2
+            | (self.rotate_right as u8) << 3
+            | (self.fire as u8) << 4
+            | (self.hyperspace as u8) << 5
+    }
+
+    /// Nombre de bits utilisés par [`PlayerInput::to_bits`].
+    pub const BITS: u32 = 6;
+
+    /// Décode des commandes encodées par [`PlayerInput::to_bits`].
+    ///
+    /// # Arguments
+    ///
+    /// * `bits` - Les commandes encodées.
+    ///
+    /// # Returns
+    ///
+    /// Les commandes, ou `None` si un bit au-delà des commandes connues est levé.
+    pub fn from_bits(bits: u8) -> Option<Self> {
+        if bits >> Self::BITS != 0 {
+            return None;
+        }
+        Some(Self {
+            thrust: bits & 1 != 0,
+            back_thrust: bits & (1 << 1) != 0,
+            rotate_left: bits & (1 << 2) != 0,
+            rotate_right: bits & (1 << 3) != 0,
+            fire: bits & (1 << 4) != 0,
+            hyperspace: bits & (1 << 5) != 0,
+        })
+    }
+}
+
+/// Evénements survenus pendant un pas de simulation.
+///
+/// L'interface s'en sert pour jouer les sons et les effets visuels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameEvent {
+    /// Un missile vient d'être tiré.
+    MissileFired,
+    /// Un missile a touché un astéroïde de taille `size` à la position `position`,
+    /// rapportant `points` points.
+    AsteroidHit { position: Vec2, size: f32, points: u32 },
+    /// Le vaisseau est entré en collision avec un astéroïde, une soucoupe ou un missile ennemi.
+    ShipHit,
+    /// Le vaisseau a ramassé un bonus de type `kind`.
+    PowerUpCollected { kind: PowerUpKind },
+    /// Un choc entre deux astéroïdes a divisé le plus petit, de taille `size`,
+    /// à la position `position`.
+    AsteroidChipped { position: Vec2, size: f32 },
+    /// Le vaisseau a sauté en hyperespace.
+    HyperspaceEntered,
+    /// Le vaisseau ressort de l'hyperespace à la position `position`.
+    HyperspaceExited { position: Vec2 },
+    /// Le vaisseau touché réapparaît au centre, libre d'astéroïdes.
+    ShipRespawned,
+    /// Une soucoupe de type `kind` entre dans l'écran.
+    SaucerAppeared { kind: SaucerKind },
+    /// Une soucoupe vient de tirer un missile.
+    SaucerFired,
+    /// Un missile a détruit une soucoupe de type `kind` à la position `position`,
+    /// rapportant `points` points.
+    SaucerDestroyed { position: Vec2, kind: SaucerKind, points: u32 },
+    /// Le bouclier du vaisseau est épuisé.
+    GameOver,
+    /// Tous les astéroïdes de la vague `wave` ont été détruits.
+    WaveCleared { wave: u32 },
+    /// La vague `wave` commence.
+    WaveStarted { wave: u32 },
+}
+
+/// Etat complet d'une partie : le vaisseau, les astéroïdes, les soucoupes ennemies et les missiles.
+///
+/// La partie enchaîne des vagues sans fin : quand une vague est nettoyée, la suivante
+/// apparaît après une courte transition, avec plus d'astéroïdes et plus rapides.
+///
+/// Tout l'aléatoire de la partie provient d'un unique générateur initialisé
+/// avec une graine : deux parties de même graine et de mêmes commandes sont identiques.
+/// L'état est sérialisable (générateur compris) pour sauvegarder une partie en cours.
+#[derive(Serialize, Deserialize)]
+pub struct GameWorld {
+    seed: u64,               // Graine du générateur aléatoire
+    rng: Xoshiro256PlusPlus, // Générateur aléatoire de la partie
+    ticks: u64,              // Nombre de pas simulés depuis le début de la partie
+    config: GameConfig,      // Réglages du vaisseau, des missiles et des astéroïdes
+    spaceship: Spaceship,
+    asteroids: Vec<Asteroid>,
+    missiles: Vec<Missile>,
+    saucers: Vec<Saucer>,
+    enemy_missiles: Vec<Missile>, // Missiles tirés par les soucoupes
+    next_saucer_in: f32,          // Temps restant avant l'apparition de la prochaine soucoupe
+    power_ups: Vec<PowerUp>,      // Bonus à ramasser
+    effects: ActiveEffects,       // Bonus temporaires actifs
+    fire_held: bool,              // Bouton de tir enfoncé au pas précédent
+    weapon: Weapon,               // Cadence, plafond de missiles et surchauffe
+    wave: u32,                  // Numéro de la vague en cours, à partir de 1
+    next_wave_in: Option<f32>,  // Temps restant avant la vague suivante, pendant une transition
+    score: u32,                 // Points gagnés depuis le début de la partie
+    is_game_over: bool,
+}
+
+impl GameWorld {
+    /// Durée d'un pas de simulation en secondes.
+    pub const TICK: f32 = 1.0 / 60.0;
+    /// Taille du monde de jeu, indépendante de la fenêtre dans laquelle il est affiché
+    /// (voir [`crate::viewport`]).
+    pub const SIZE: (f32, f32) = (800.0, 600.0);
+
+    /// Crée une nouvelle partie : un vaisseau au centre et la première vague d'astéroïdes.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - La graine du générateur aléatoire de la partie.
+    pub fn new(seed: u64) -> Self {
+        Self::with_config(seed, GameConfig::default())
+    }
+
+    /// Crée une nouvelle partie avec des réglages donnés.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - La graine du générateur aléatoire de la partie.
+    /// * `config` - Les réglages du jeu.
+    pub fn with_config(seed: u64, config: GameConfig) -> Self {
+        let mut world = Self {
+            seed,
+            rng: Xoshiro256PlusPlus::seed_from_u64(seed),
+            ticks: 0,
+            config,
+            spaceship: Spaceship::with_config(config.ship),
+            asteroids: Vec::new(),
+            missiles: Vec::new(),
+            saucers: Vec::new(),
+            enemy_missiles: Vec::new(),
+            next_saucer_in: 0.0,
+            power_ups: Vec::new(),
+            effects: ActiveEffects::default(),
+            fire_held: false,
+            weapon: Weapon::with_config(config.weapon),
+            wave: 1,
+            next_wave_in: None,
+            score: 0,
+            is_game_over: false,
+        };
+        world.spawn_wave();
+        world.next_saucer_in = world.saucer_delay();
+        world
+    }
+
+    /// Tire le délai avant l'apparition de la prochaine soucoupe, autour de `saucer.spawn_interval`.
+    fn saucer_delay(&mut self) -> f32 {
+        self.config.saucer.spawn_interval * self.rng.gen_range(0.5..1.5)
+    }
+
+    /// Fait apparaître les astéroïdes de la vague en cours.
+    ///
+    /// Chaque vague compte `wave.extra_asteroids` astéroïdes de plus que la précédente,
+    /// et leur vitesse est multipliée par `wave.speed_factor`.
+    fn spawn_wave(&mut self) {
+        let asteroid = self.config.asteroid;
+        let level = self.wave - 1;
+        let wave = self.config.wave;
+        // La difficulté augmente à chaque vague, jusqu'aux plafonds des réglages
+        let n_asteroid = self.rng.gen_range(asteroid.min_count..=asteroid.max_count)
+            .saturating_add(level.saturating_mul(wave.extra_asteroids))
+            .min(wave.max_count);
+        let mut wave_config = asteroid;
+        wave_config.speed = (asteroid.speed * wave.speed_factor.powi(i32::try_from(level).unwrap_or(i32::MAX)))
+            .min(wave.max_speed);
+        for _ in 0..n_asteroid {
+            self.asteroids.push(Asteroid::with_config(asteroid.init_size, wave_config, &mut self.rng));
+        }
+    }
+
+    /// Retourne le vaisseau.
+    pub fn spaceship(&self) -> &Spaceship {
+        &self.spaceship
+    }
+
+    /// Retourne les astéroïdes encore présents.
+    pub fn asteroids(&self) -> &[Asteroid] {
+        &self.asteroids
+    }
+
+    /// Retourne les missiles en vol.
+    pub fn missiles(&self) -> &[Missile] {
+        &self.missiles
+    }
+
+    /// Retourne les soucoupes ennemies présentes.
+    pub fn saucers(&self) -> &[Saucer] {
+        &self.saucers
+    }
+
+    /// Retourne les missiles tirés par les soucoupes.
+    pub fn enemy_missiles(&self) -> &[Missile] {
+        &self.enemy_missiles
+    }
+
+    /// Retourne les bonus à ramasser.
+    pub fn power_ups(&self) -> &[PowerUp] {
+        &self.power_ups
+    }
+
+    /// Retourne les bonus temporaires actifs.
+    pub fn effects(&self) -> &ActiveEffects {
+        &self.effects
+    }
+
+    /// Retourne l'arme du vaisseau.
+    pub fn weapon(&self) -> &Weapon {
+        &self.weapon
+    }
+
+    /// Retourne la graine avec laquelle la partie a été créée.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Retourne les réglages de la partie.
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// Retourne le nombre de pas simulés depuis le début de la partie.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Retourne le temps de jeu écoulé.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_secs_f64(self.ticks as f64 * Self::TICK as f64)
+    }
+
+    /// Retourne la taille du monde de jeu, toujours [`GameWorld::SIZE`].
+    pub fn screen_size(&self) -> (f32, f32) {
+        Self::SIZE
+    }
+
+    /// Indique si la partie est perdue.
+    pub fn is_game_over(&self) -> bool {
+        self.is_game_over
+    }
+
+    /// Retourne le numéro de la vague en cours, à partir de 1.
+    pub fn wave(&self) -> u32 {
+        self.wave
+    }
+
+    /// Retourne le score de la partie.
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    /// Retourne le temps restant avant la vague suivante, si une transition est en cours.
+    pub fn next_wave_in(&self) -> Option<f32> {
+        self.next_wave_in
+    }
+
+    /// Avance la simulation d'un pas de durée [`GameWorld::TICK`].
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Les commandes du joueur pour ce pas.
+    ///
+    /// # Returns
+    ///
+    /// Les événements survenus pendant ce pas. Une partie terminée n'évolue plus
+    /// et renvoie un vecteur vide.
+    pub fn step(&mut self, input: &PlayerInput) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        if self.is_game_over {
+            return events;
+        }
+        self.ticks += 1;
+        self.weapon.update(Self::TICK);
+        self.apply_input(input, &mut events);
+        self.update_model(&mut events);
+
+        if self.spaceship.is_hyperspace_over() {
+            let position = self.hyperspace_destination();
+            self.spaceship.exit_hyperspace(position);
+            events.push(GameEvent::HyperspaceExited { position });
+        }
+        if self.spaceship.is_respawning() && !self.spaceship.is_destroyed() && self.is_centre_clear() {
+            self.spaceship.respawn();
+            events.push(GameEvent::ShipRespawned);
+        }
+
+        if self.spaceship.is_destroyed() {
+            self.is_game_over = true;
+            events.push(GameEvent::GameOver);
+        } else if let Some(remaining) = self.next_wave_in {
+            // Transition entre deux vagues
+            let remaining = remaining - Self::TICK;
+            if remaining > 0.0 {
+                self.next_wave_in = Some(remaining);
+            } else {
+                self.next_wave_in = None;
+                self.wave = self.wave.saturating_add(1);
+                self.spawn_wave();
+                events.push(GameEvent::WaveStarted { wave: self.wave });
+            }
+        } else if self.asteroids.is_empty() {
+            events.push(GameEvent::WaveCleared { wave: self.wave });
+            self.next_wave_in = Some(self.config.wave.transition_time);
+        }
+        events
+    }
+
+    /// Indique si aucun astéroïde n'est à moins de `ship.respawn_clear_radius` du centre.
+    fn is_centre_clear(&self) -> bool {
+        let centre = vec2(Self::SIZE.0 / 2.0, Self::SIZE.1 / 2.0);
+        let radius = self.config.ship.respawn_clear_radius;
+        self.asteroids
+            .iter()
+            .all(|a| torus::distance(a.get_position(), centre, Self::SIZE) >= radius + a.get_size())
+    }
+
+    /// Tire la position d'arrivée d'un saut en hyperespace : n'importe où à l'écran,
+    /// ou dans un astéroïde avec la probabilité `ship.hyperspace_risk`.
+    fn hyperspace_destination(&mut self) -> Vec2 {
+        let in_asteroid = self.rng.gen_bool(self.config.ship.hyperspace_risk);
+        if in_asteroid && !self.asteroids.is_empty() {
+            let index = self.rng.gen_range(0..self.asteroids.len());
+            return self.asteroids[index].get_position();
+        }
+        vec2(
+            self.rng.gen_range(0.0..Self::SIZE.0),
+            self.rng.gen_range(0.0..Self::SIZE.1),
+        )
+    }
+
+    /// Applique les commandes du joueur au vaisseau.
+    /// Un vaisseau invisible (en attente de réapparition ou en hyperespace) ignore les commandes.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Les commandes du joueur.
+    /// * `events` - Les événements du pas en cours.
+    fn apply_input(&mut self, input: &PlayerInput, events: &mut Vec<GameEvent>) {
+        if !self.spaceship.is_visible() {
+            return;
+        }
+        if input.hyperspace && self.spaceship.can_jump() {
+            self.spaceship.enter_hyperspace();
+            events.push(GameEvent::HyperspaceEntered);
+            return;
+        }
+        if input.thrust {
+            self.spaceship.activate_thrust(Self::TICK);
+        }
+        if input.back_thrust {
+            self.spaceship.back_thrust(Self::TICK);
+        }
+        if input.rotate_left {
+            self.spaceship.left_rotation(Self::TICK);
+        }
+        if input.rotate_right {
+            self.spaceship.right_rotation(Self::TICK);
+        }
+        // Tir à l'appui, puis tir automatique sous l'effet du tir rapide,
+        // dans les limites de l'arme
+        let pressed = input.fire && !self.fire_held;
+        self.fire_held = input.fire;
+        let rapid_fire = self.effects.is_active(PowerUpKind::RapidFire);
+        if (pressed || input.fire && rapid_fire) && self.weapon.can_fire(self.missiles.len()) {
+            let interval = if rapid_fire { self.config.powerup.rapid_fire_interval } else { self.config.weapon.cooldown };
+            self.weapon.fire(interval);
+            self.fire(events);
+        }
+    }
+
+    /// Tire un missile dans la direction du vaisseau, ou trois en éventail sous l'effet
+    /// du tir multiple, sans dépasser `weapon.max_missiles` en vol : l'éventail perd
+    /// ses côtés s'il n'y a plus la place. Les missiles sont perçants sous l'effet des
+    /// missiles perçants, et emportent la vitesse du vaisseau si `missile.inherit_velocity` est activé.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - Les événements du pas en cours.
+    fn fire(&mut self, events: &mut Vec<GameEvent>) {
+        let orientation = self.spaceship.get_orientation();
+        let spread = self.config.powerup.multi_shot_spread;
+        let mut orientations = if self.effects.is_active(PowerUpKind::MultiShot) {
+            vec![orientation, orientation - spread, orientation + spread]
+        } else {
+            vec![orientation]
+        };
+        orientations.truncate(self.weapon.max_missiles().saturating_sub(self.missiles.len()));
+        for orientation in orientations {
+            let mut missile = Missile::with_config(
+                self.spaceship.get_position(),
+                orientation,
+                self.config.missile,
+            );
+            missile.set_piercing(self.effects.is_active(PowerUpKind::Piercing));
+            if self.config.missile.inherit_velocity {
+                missile.add_velocity(self.spaceship.get_speed());
+            }
+            self.missiles.push(missile);
+        }
+        events.push(GameEvent::MissileFired);
+    }
+
+    /// Met à jour les astéroïdes, les soucoupes, les missiles et le vaisseau, et gère les collisions.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - Les événements du pas en cours.
+    fn update_model(&mut self, events: &mut Vec<GameEvent>) {
+        // Mettre à jour la position des missiles
+        for missile in self.missiles.iter_mut() {
+            missile.update(Self::TICK);
+        }
+
+        // Ranger les missiles dans la grille, pour ne tester que ceux proches de chaque astéroïde
+        let mut grid = self.new_grid();
+        for (index, missile) in self.missiles.iter().enumerate() {
+            grid.insert(index, missile.get_position(), 0.0);
+        }
+        let mut nearby = Vec::new();
+
+        let mut new_asteroids = Vec::new();
+        for asteroid in self.asteroids.iter_mut() {
+            asteroid.update(Self::TICK); // Déplace chaque astéroïde.
+            // Direction de l'impact qui divise l'astéroïde, s'il est touché
+            let mut impact = None;
+            // Vérifier la collision entre le vaisseau et l'astéroïde
+            if self.spaceship.check_collision(asteroid) {
+                events.push(GameEvent::ShipHit);
+                // Diviser l asteroid si il y a une collision entre le vaisseau et l asteroid
+                impact = Some(asteroid.get_position() - self.spaceship.get_position());
+            } else {
+                // Parcourir les missiles proches pour vérifier les collisions
+                grid.query(asteroid.get_position(), asteroid.get_size() * Asteroid::OUTLINE_MAX_RADIUS, &mut nearby);
+                for &index in nearby.iter() {
+                    let missile = &mut self.missiles[index];
+                    // Vérifier la collision entre le missile et l'astéroïde
+                    if missile.check_collision(asteroid) {
+                        impact = Some(missile.get_speed());
+                        let points = self.config.points_for(asteroid.get_size());
+                        self.score += points;
+                        events.push(GameEvent::AsteroidHit {
+                            position: asteroid.get_position(),
+                            size: asteroid.get_size(),
+                            points,
+                        });
+                        // Il lâche parfois un bonus
+                        if self.rng.gen_bool(self.config.powerup.drop_chance) {
+                            let kind = PowerUpKind::ALL[self.rng.gen_range(0..PowerUpKind::ALL.len())];
+                            self.power_ups.push(PowerUp::with_config(
+                                kind,
+                                asteroid.get_position(),
+                                self.config.powerup,
+                                &mut self.rng,
+                            ));
+                        }
+                        break;
+                    }
+                }
+            }
+
+            match impact {
+                // Si l'astéroïde a été touché, le diviser et le supprimer
+                Some(impact) => new_asteroids.extend(asteroid.split_asteroid(impact, &mut self.rng)),
+                // Sinon, le conserver
+                None => new_asteroids.push(asteroid.clone()),
+            }
+        }
+        self.update_saucers(events);
+        self.missiles.retain(|m| m.is_active());
+        self.update_power_ups(events);
+
+        // Mettre à jour la liste des astéroïdes et le vaisseau
+        self.asteroids = new_asteroids;
+        if self.config.asteroid.bounce {
+            self.bounce_asteroids(events);
+        }
+        self.spaceship.update(Self::TICK);
+    }
+
+    /// Crée une grille vide pour la détection large des collisions, avec des cases
+    /// à la mesure des plus gros astéroïdes.
+    fn new_grid(&self) -> SpatialHash {
+        SpatialHash::new(Self::SIZE, 2.0 * self.config.asteroid.init_size * Asteroid::OUTLINE_MAX_RADIUS)
+    }
+
+    /// Fait rebondir les astéroïdes qui se touchent. Si `asteroid.chip` est activé,
+    /// un choc assez violent divise le plus petit des deux.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - Les événements du pas en cours.
+    fn bounce_asteroids(&mut self, events: &mut Vec<GameEvent>) {
+        let config = self.config.asteroid;
+        // Direction du choc subi par chaque astéroïde à diviser
+        let mut chipped: Vec<Option<Vec2>> = vec![None; self.asteroids.len()];
+        let mut grid = self.new_grid();
+        let mut nearby = Vec::new();
+        for j in 0..self.asteroids.len() {
+            let (left, right) = self.asteroids.split_at_mut(j);
+            let b = &mut right[0];
+            let reach = b.get_size() * Asteroid::OUTLINE_MAX_RADIUS;
+            // Seuls les astéroïdes déjà rangés, proches de celui-ci, peuvent le toucher
+            grid.query(b.get_position(), reach, &mut nearby);
+            grid.insert(j, b.get_position(), reach);
+            for &i in nearby.iter() {
+                let a = &mut left[i];
+                let Some(impact) = a.bounce(b) else {
+                    continue;
+                };
+                if config.chip && impact > config.chip_speed && a.get_size() != b.get_size() {
+                    let direction = b.get_position() - a.get_position();
+                    if a.get_size() < b.get_size() {
+                        chipped[i] = Some(-direction);
+                    } else {
+                        chipped[j] = Some(direction);
+                    }
+                }
+            }
+        }
+
+        let asteroids = std::mem::take(&mut self.asteroids);
+        for (asteroid, impact) in asteroids.into_iter().zip(chipped) {
+            match impact {
+                Some(impact) => {
+                    let fragments = asteroid.split_asteroid(impact, &mut self.rng);
+                    if fragments.is_empty() {
+                        // Les petits astéroïdes ne se divisent plus
+                        self.asteroids.push(asteroid);
+                    } else {
+                        events.push(GameEvent::AsteroidChipped {
+                            position: asteroid.get_position(),
+                            size: asteroid.get_size(),
+                        });
+                        self.asteroids.extend(fragments);
+                    }
+                }
+                None => self.asteroids.push(asteroid),
+            }
+        }
+    }
+
+    /// Déplace les bonus, retire ceux qui ont expiré et applique ceux que le vaisseau ramasse.
+    /// Un vaisseau invisible ne ramasse rien.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - Les événements du pas en cours.
+    fn update_power_ups(&mut self, events: &mut Vec<GameEvent>) {
+        self.effects.update(Self::TICK);
+        let mut power_ups = std::mem::take(&mut self.power_ups);
+        for power_up in power_ups.iter_mut() {
+            power_up.update(Self::TICK);
+            if self.spaceship.is_visible() && power_up.check_collision(&self.spaceship) {
+                let kind = power_up.kind();
+                match kind {
+                    PowerUpKind::ShieldRecharge => self.spaceship.recharge_shield(self.config.powerup.max_shield),
+                    _ => self.effects.activate(kind, self.config.powerup.duration),
+                }
+                events.push(GameEvent::PowerUpCollected { kind });
+            } else if !power_up.is_expired() {
+                self.power_ups.push(power_up.clone());
+            }
+        }
+    }
+
+    /// Fait apparaître, déplace et tirer les soucoupes, déplace leurs missiles,
+    /// et gère leurs collisions avec le vaisseau et les missiles du joueur.
+    ///
+    /// Une seule soucoupe est présente à la fois, et aucune n'apparaît pendant
+    /// une transition entre deux vagues. Les missiles ennemis ne touchent que le vaisseau.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - Les événements du pas en cours.
+    fn update_saucers(&mut self, events: &mut Vec<GameEvent>) {
+        if self.saucers.is_empty() && self.next_wave_in.is_none() {
+            self.next_saucer_in -= Self::TICK;
+            if self.next_saucer_in <= 0.0 {
+                let kind = if self.rng.gen_bool(self.config.saucer.small_chance) {
+                    SaucerKind::Small
+                } else {
+                    SaucerKind::Large
+                };
+                self.saucers.push(Saucer::with_config(kind, self.config.saucer, &mut self.rng));
+                self.next_saucer_in = self.saucer_delay();
+                events.push(GameEvent::SaucerAppeared { kind });
+            }
+        }
+
+        let target = self.spaceship.get_position();
+        for saucer in self.saucers.iter_mut() {
+            saucer.update(Self::TICK);
+            if let Some(missile) = saucer.think(Self::TICK, target, &mut self.rng) {
+                self.enemy_missiles.push(missile);
+                events.push(GameEvent::SaucerFired);
+            }
+            // Vérifier la collision entre le vaisseau et la soucoupe, détruite dans le choc
+            if saucer.is_active() && self.spaceship.check_collision(saucer) {
+                saucer.destroy();
+                events.push(GameEvent::ShipHit);
+                continue;
+            }
+            for missile in self.missiles.iter_mut() {
+                if saucer.is_active() && missile.check_collision(saucer) {
+                    let points = match saucer.kind() {
+                        SaucerKind::Large => self.config.score.large_saucer,
+                        SaucerKind::Small => self.config.score.small_saucer,
+                    };
+                    self.score += points;
+                    saucer.destroy();
+                    events.push(GameEvent::SaucerDestroyed {
+                        position: saucer.get_position(),
+                        kind: saucer.kind(),
+                        points,
+                    });
+                    break;
+                }
+            }
+        }
+        self.saucers.retain(|s| s.is_active());
+
+        for missile in self.enemy_missiles.iter_mut() {
+            missile.update(Self::TICK);
+            if missile.is_active() && self.spaceship.check_collision(missile) {
+                missile.deactivate();
+                events.push(GameEvent::ShipHit);
+            }
+        }
+        self.enemy_missiles.retain(|m| m.is_active());
+    }
+}
+
+/// Tests unitaires
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_world() {
+        let world = GameWorld::new(0);
+
+        assert!((4..=8).contains(&world.asteroids().len()));
+        assert!(world.missiles().is_empty());
+        assert_eq!(world.spaceship().get_position(), vec2(400.0, 300.0));
+        assert!(!world.is_game_over());
+        assert_eq!(world.wave(), 1);
+    }
+
+    #[test]
+    fn test_fire_missile() {
+        let mut world = GameWorld::new(0);
+        world.asteroids.clear();
+        world.asteroids.push(Asteroid::new(Asteroid::ASTEROID_INIT_SIZE, &mut world.rng));
+        world.asteroids[0].set_position(vec2(50.0, 550.0));
+
+        let events = world.step(&PlayerInput { fire: true, ..Default::default() });
+
+        assert!(events.contains(&GameEvent::MissileFired));
+        assert_eq!(world.missiles().len(), 1);
+    }
+
+    #[test]
+    fn test_score() {
+        let mut world = GameWorld::new(0);
+        world.asteroids.truncate(1);
+        // Astéroïde juste devant le vaisseau, sur la trajectoire du missile
+        world.asteroids[0].set_position(vec2(400.0, 200.0));
+
+        let events: Vec<_> = (0..20)
+            .flat_map(|i| world.step(&PlayerInput { fire: i == 0, ..Default::default() }))
+            .collect();
+
+        let points = world.config().score.large;
+        assert!(events.iter().any(|e| matches!(e, GameEvent::AsteroidHit { points: p, .. } if *p == points)));
+        assert_eq!(world.score(), points);
+    }
+
+    #[test]
+    fn test_game_over() {
+        let mut world = GameWorld::new(0);
+        for _ in 1..Spaceship::INITIAL_SHIELD {
+            world.spaceship.decrease_shield();
+        }
+        // Un astéroïde sur le vaisseau détruit le dernier niveau de bouclier
+        let center = world.spaceship().get_position();
+        world.asteroids[0].set_position(center);
+
+        let events = world.step(&PlayerInput::default());
+
+        assert!(events.contains(&GameEvent::ShipHit));
+        assert!(events.contains(&GameEvent::GameOver));
+        assert!(world.is_game_over());
+        // Une partie terminée n'évolue plus
+        assert!(world.step(&PlayerInput::default()).is_empty());
+    }
+
+    #[test]
+    fn test_game_over_keeps_other_asteroids() {
+        let mut world = GameWorld::new(0);
+        for _ in 1..Spaceship::INITIAL_SHIELD {
+            world.spaceship.decrease_shield();
+        }
+        let count = world.asteroids().len();
+        let center = world.spaceship().get_position();
+        world.asteroids[0].set_position(center);
+        let fragments = world.config.asteroid.fragment_count(world.asteroids[0].get_size()) as usize;
+
+        world.step(&PlayerInput::default());
+
+        // Seul l'astéroïde heurté est remplacé par ses fragments
+        assert!(world.is_game_over());
+        assert_eq!(world.asteroids().len(), count - 1 + fragments);
+    }
+
+    #[test]
+    fn test_respawn_waits_for_clear_centre() {
+        let mut world = GameWorld::new(0);
+        world.asteroids.truncate(1);
+        let center = world.spaceship().get_position();
+        world.asteroids[0].set_position(center);
+
+        let events = world.step(&PlayerInput::default());
+        assert!(events.contains(&GameEvent::ShipHit));
+        assert!(world.spaceship().is_respawning());
+
+        // Fragments de l'astéroïde au centre : le vaisseau attend, sans perdre de bouclier
+        let fragments = world.asteroids.clone();
+        for _ in 0..10 {
+            world.asteroids = fragments.clone();
+            for asteroid in world.asteroids.iter_mut() {
+                asteroid.set_position(center);
+            }
+            let events = world.step(&PlayerInput { fire: true, ..Default::default() });
+            assert!(events.is_empty());
+        }
+        assert_eq!(world.spaceship().get_shield(), Spaceship::INITIAL_SHIELD - 1);
+        assert!(world.missiles().is_empty());
+
+        world.asteroids.clear();
+        let events = world.step(&PlayerInput::default());
+        assert!(events.contains(&GameEvent::ShipRespawned));
+        assert!(!world.spaceship().is_respawning());
+        assert!(world.spaceship().is_invulnerable());
+    }
+
+    #[test]
+    fn test_saucer_appears_and_fires() {
+        let mut world = GameWorld::new(0);
+        world.next_saucer_in = GameWorld::TICK;
+
+        let events = world.step(&PlayerInput::default());
+        assert!(events.iter().any(|e| matches!(e, GameEvent::SaucerAppeared { .. })));
+        assert_eq!(world.saucers().len(), 1);
+
+        let fire_ticks = (world.config().saucer.fire_interval / GameWorld::TICK).ceil() as usize;
+        let events: Vec<_> = (0..=fire_ticks).flat_map(|_| world.step(&PlayerInput::default())).collect();
+        assert!(events.contains(&GameEvent::SaucerFired));
+        assert!(!world.enemy_missiles().is_empty());
+    }
+
+    #[test]
+    fn test_enemy_missile_hits_ship() {
+        let mut world = GameWorld::new(0);
+        world.asteroids.truncate(1);
+        world.asteroids[0].set_position(vec2(50.0, 50.0));
+        // Missile ennemi juste sous le vaisseau, filant vers le haut
+        let center = world.spaceship().get_position();
+        world.enemy_missiles.push(Missile::new(center + vec2(0.0, 20.0), 0.0));
+
+        let events = world.step(&PlayerInput::default());
+
+        assert!(events.contains(&GameEvent::ShipHit));
+        assert_eq!(world.spaceship().get_shield(), Spaceship::INITIAL_SHIELD - 1);
+        assert!(world.enemy_missiles().is_empty());
+    }
+
+    #[test]
+    fn test_shoot_saucer() {
+        let mut world = GameWorld::new(0);
+        world.asteroids.truncate(1);
+        world.asteroids[0].set_position(vec2(50.0, 50.0));
+        let mut saucer = Saucer::new(SaucerKind::Small, &mut world.rng);
+        saucer.set_position(vec2(400.0, 250.0));
+        world.saucers.push(saucer);
+        // Missile juste sous la soucoupe, filant vers elle
+        world.missiles.push(Missile::new(vec2(400.0, 262.0), 0.0));
+
+        let events = world.step(&PlayerInput::default());
+
+        let points = world.config().score.small_saucer;
+        assert!(events.iter().any(|e| matches!(e, GameEvent::SaucerDestroyed { kind: SaucerKind::Small, .. })));
+        assert_eq!(world.score(), points);
+        assert!(world.saucers().is_empty());
+    }
+
+    #[test]
+    fn test_fire_on_press() {
+        let mut world = GameWorld::new(0);
+        world.asteroids.clear();
+        let fire = PlayerInput { fire: true, ..Default::default() };
+        let shots = |world: &mut GameWorld| {
+            (0..30).flat_map(|_| world.step(&fire)).filter(|e| *e == GameEvent::MissileFired).count()
+        };
+
+        // Maintenir le bouton ne tire qu'une fois sans tir rapide
+        assert_eq!(shots(&mut world), 1);
+
+        // Le tir rapide tire à intervalles réguliers tant que le bouton reste enfoncé
+        world.effects.activate(PowerUpKind::RapidFire, 10.0);
+        let interval = world.config().powerup.rapid_fire_interval;
+        let expected = 30.0 * GameWorld::TICK / interval;
+        assert!((shots(&mut world) as f32 - expected).abs() <= 1.0);
+    }
+
+    #[test]
+    fn test_fire_cooldown_and_cap() {
+        let mut config = GameConfig::default();
+        config.weapon.heat_per_shot = 0.0;
+        config.weapon.max_missiles = 3;
+        let mut world = GameWorld::with_config(0, config);
+        world.asteroids.clear();
+
+        // Appuyer à chaque pas ne dépasse pas la cadence de l'arme, ni le plafond de missiles
+        let events: Vec<_> = (0..60)
+            .flat_map(|i| world.step(&PlayerInput { fire: i % 2 == 0, ..Default::default() }))
+            .collect();
+
+        assert_eq!(events.iter().filter(|e| **e == GameEvent::MissileFired).count(), 3);
+        assert_eq!(world.missiles().len(), 3);
+    }
+
+    #[test]
+    fn test_overheat() {
+        let mut world = GameWorld::new(0);
+        world.asteroids.clear();
+        world.effects.activate(PowerUpKind::RapidFire, 100.0);
+
+        // Le tir rapide maintenu finit par faire surchauffer l'arme
+        for _ in 0..600 {
+            world.step(&PlayerInput { fire: true, ..Default::default() });
+            if world.weapon().is_overheated() {
+                break;
+            }
+        }
+        assert!(world.weapon().is_overheated());
+        let events = world.step(&PlayerInput { fire: true, ..Default::default() });
+        assert!(!events.contains(&GameEvent::MissileFired));
+    }
+
+    #[test]
+    fn test_multi_shot() {
+        let mut world = GameWorld::new(0);
+        world.asteroids.clear();
+        world.effects.activate(PowerUpKind::MultiShot, 10.0);
+
+        let events = world.step(&PlayerInput { fire: true, ..Default::default() });
+
+        assert_eq!(events.iter().filter(|e| **e == GameEvent::MissileFired).count(), 1);
+        assert_eq!(world.missiles().len(), 3);
+    }
+
+    #[test]
+    fn test_multi_shot_respects_cap() {
+        let mut config = GameConfig::default();
+        config.weapon.max_missiles = 4;
+        config.weapon.cooldown = 0.0;
+        let mut world = GameWorld::with_config(0, config);
+        world.asteroids.clear();
+        world.effects.activate(PowerUpKind::MultiShot, 10.0);
+
+        // Un premier éventail de trois missiles, puis un seul : le missile central
+        world.step(&PlayerInput { fire: true, ..Default::default() });
+        world.step(&PlayerInput::default());
+        world.step(&PlayerInput { fire: true, ..Default::default() });
+
+        assert_eq!(world.missiles().len(), 4);
+        let heading = |m: &Missile| m.get_speed().normalize();
+        assert!((heading(&world.missiles()[3]) - vec2(0.0, -1.0)).length() < 1e-4);
+    }
+
+    #[test]
+    fn test_collect_shield_recharge() {
+        let mut world = GameWorld::new(0);
+        world.asteroids.truncate(1);
+        world.asteroids[0].set_position(vec2(50.0, 50.0));
+        let center = world.spaceship().get_position();
+        let power_up = PowerUp::with_config(
+            PowerUpKind::ShieldRecharge, center, world.config.powerup, &mut world.rng,
+        );
+        world.power_ups.push(power_up.clone());
+
+        let events = world.step(&PlayerInput::default());
+        assert!(events.contains(&GameEvent::PowerUpCollected { kind: PowerUpKind::ShieldRecharge }));
+        assert_eq!(world.spaceship().get_shield(), Spaceship::INITIAL_SHIELD + 1);
+        assert!(world.power_ups().is_empty());
+
+        // Le bouclier ne dépasse pas le maximum
+        for _ in 0..10 {
+            world.power_ups.push(power_up.clone());
+            world.step(&PlayerInput::default());
+        }
+        assert_eq!(world.spaceship().get_shield(), world.config().powerup.max_shield);
+    }
+
+    #[test]
+    fn test_hyperspace() {
+        let mut world = GameWorld::new(0);
+        let jump = PlayerInput { hyperspace: true, ..Default::default() };
+
+        let events = world.step(&jump);
+        assert!(events.contains(&GameEvent::HyperspaceEntered));
+        assert!(world.spaceship().is_in_hyperspace());
+
+        let ticks = (world.config().ship.hyperspace_time / GameWorld::TICK).ceil() as usize;
+        let events: Vec<_> = (0..=ticks).flat_map(|_| world.step(&jump)).collect();
+        assert!(events.iter().any(|e| matches!(e, GameEvent::HyperspaceExited { .. })));
+        // Le délai empêche de ressauter aussitôt
+        assert!(!events.contains(&GameEvent::HyperspaceEntered));
+        assert!(world.spaceship().is_visible());
+    }
+
+    #[test]
+    fn test_hyperspace_into_asteroid() {
+        let mut config = GameConfig::default();
+        config.ship.hyperspace_risk = 1.0;
+        let mut world = GameWorld::with_config(0, config);
+        world.asteroids.truncate(1);
+
+        let ticks = (config.ship.hyperspace_time / GameWorld::TICK).ceil() as usize + 2;
+        let events: Vec<_> = (0..=ticks)
+            .flat_map(|i| world.step(&PlayerInput { hyperspace: i == 0, ..Default::default() }))
+            .collect();
+
+        assert!(events.contains(&GameEvent::ShipHit));
+        assert_eq!(world.spaceship().get_shield(), Spaceship::INITIAL_SHIELD - 1);
+    }
+
+    /// Crée une partie avec un grand astéroïde à gauche et un moyen à droite,
+    /// qui foncent l'un vers l'autre à `speed` unités par seconde, loin du vaisseau.
+    fn head_on(config: GameConfig, speed: f32) -> GameWorld {
+        let mut world = GameWorld::with_config(0, config);
+        world.asteroids.clear();
+        for (size, x, direction) in [(config.asteroid.init_size, 200.0, 1.0), (config.asteroid.medium_size, 320.0, -1.0)] {
+            let mut asteroid = Asteroid::with_config(size, config.asteroid, &mut world.rng);
+            asteroid.set_position(vec2(x, 100.0));
+            asteroid.set_speed(vec2(direction * speed, 0.0));
+            world.asteroids.push(asteroid);
+        }
+        world
+    }
+
+    #[test]
+    fn test_asteroids_bounce_only_when_enabled() {
+        let mut world = head_on(GameConfig::default(), 60.0);
+        for _ in 0..120 {
+            world.step(&PlayerInput::default());
+        }
+        // Sans rebond, les astéroïdes se traversent
+        assert!(world.asteroids()[0].get_position().x > world.asteroids()[1].get_position().x);
+
+        let mut config = GameConfig::default();
+        config.asteroid.bounce = true;
+        let mut world = head_on(config, 60.0);
+        for _ in 0..120 {
+            world.step(&PlayerInput::default());
+        }
+        let (large, medium) = (&world.asteroids()[0], &world.asteroids()[1]);
+        assert_eq!(world.asteroids().len(), 2);
+        assert!(large.get_position().x < medium.get_position().x);
+        assert!(medium.get_speed().x > 0.0);
+    }
+
+    #[test]
+    fn test_violent_bounce_chips_smaller_asteroid() {
+        let mut config = GameConfig::default();
+        config.asteroid.bounce = true;
+        config.asteroid.chip = true;
+        let mut world = head_on(config, config.asteroid.chip_speed);
+
+        let events: Vec<_> = (0..120).flat_map(|_| world.step(&PlayerInput::default())).collect();
+
+        let chipped: Vec<_> = events.iter().filter(|e| matches!(e, GameEvent::AsteroidChipped { .. })).collect();
+        assert_eq!(chipped.len(), 1);
+        assert!(matches!(chipped[0], GameEvent::AsteroidChipped { size, .. } if *size == config.asteroid.medium_size));
+        let sizes: Vec<_> = world.asteroids().iter().map(|a| a.get_size()).collect();
+        assert_eq!(sizes.iter().filter(|&&s| s == config.asteroid.init_size).count(), 1);
+        assert_eq!(sizes.len(), 1 + config.asteroid.fragments_medium as usize);
+    }
+
+    #[test]
+    fn test_next_wave() {
+        let mut world = GameWorld::new(0);
+        world.asteroids.clear();
+
+        let events = world.step(&PlayerInput::default());
+        assert_eq!(events, vec![GameEvent::WaveCleared { wave: 1 }]);
+        assert!(world.next_wave_in().is_some());
+
+        // La vague suivante apparaît après la transition
+        let transition_ticks = (world.config().wave.transition_time / GameWorld::TICK).ceil() as usize;
+        let events: Vec<_> = (0..=transition_ticks).flat_map(|_| world.step(&PlayerInput::default())).collect();
+        assert_eq!(events, vec![GameEvent::WaveStarted { wave: 2 }]);
+        assert_eq!(world.wave(), 2);
+        assert!(world.next_wave_in().is_none());
+        assert!(!world.asteroids().is_empty());
+        assert!(!world.is_game_over());
+    }
+
+    #[test]
+    fn test_waves_grow() {
+        let mut config = GameConfig::default();
+        // Nombre d'astéroïdes fixe pour comparer les vagues
+        config.asteroid.min_count = 5;
+        config.asteroid.max_count = 5;
+        config.wave.extra_asteroids = 2;
+        let mut world = GameWorld::with_config(0, config);
+        let speed = |w: &GameWorld| (w.asteroids[0].get_position() - w.asteroids[0].get_previous_position()).length();
+        world.step(&PlayerInput::default());
+        let first_speed = speed(&world);
+
+        world.asteroids.clear();
+        world.wave = 3;
+        world.spawn_wave();
+        world.step(&PlayerInput::default());
+
+        assert_eq!(world.asteroids().len(), 5 + 2 * 2);
+        assert!(speed(&world) > first_speed);
+    }
+
+    #[test]
+    fn test_waves_are_capped() {
+        let mut world = GameWorld::new(0);
+        let wave = world.config().wave;
+
+        world.asteroids.clear();
+        world.wave = u32::MAX;
+        world.spawn_wave();
+
+        assert_eq!(world.asteroids().len(), wave.max_count as usize);
+        assert!(world.asteroids().iter().all(|a| a.get_speed().length() <= wave.max_speed + 1e-3));
+    }
+
+    #[test]
+    fn test_same_seed_same_game() {
+        let mut w1 = GameWorld::new(42);
+        let mut w2 = GameWorld::new(42);
+        let input = PlayerInput { thrust: true, rotate_left: true, fire: true, ..Default::default() };
+
+        for _ in 0..300 {
+            assert_eq!(w1.step(&input), w2.step(&input));
+        }
+        let positions = |w: &GameWorld| w.asteroids().iter().map(|a| a.get_position()).collect::<Vec<_>>();
+        assert_eq!(positions(&w1), positions(&w2));
+        assert_eq!(w1.spaceship().get_position(), w2.spaceship().get_position());
+    }
+}